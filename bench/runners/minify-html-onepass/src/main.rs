@@ -15,6 +15,7 @@ fn main() {
     let cfg = Cfg {
         minify_css: !html_only,
         minify_js: !html_only,
+        ..Cfg::new()
     };
 
     for t in tests {
@@ -1,9 +1,14 @@
 use std::fs::File;
 use std::io::{stdin, stdout, Read, Write};
+use std::sync::Arc;
 
 use structopt::StructOpt;
 
-use minify_html::{minify, Cfg};
+use minify_html::{
+    minify_with_diagnostics, minify_with_name_map, Cfg, CommentRule, CssOptions, EsTarget,
+    JsOptions, MinifyCache, NameMap, RedundantAttributeCategory, WhitespaceMinification,
+};
+use serde_json::{json, Map, Value};
 
 #[derive(StructOpt)]
 #[structopt(
@@ -18,33 +23,183 @@ struct Cli {
     /// Output destination; omit for stdout.
     #[structopt(short, long, parse(from_os_str))]
     output: Option<std::path::PathBuf>,
+    /// Escape all non-ASCII characters so that the output only contains ASCII. C1 control characters (U+0080 to U+009F) in text and attribute values are left as is, as they can't be escaped.
+    #[structopt(long)]
+    ascii_only: bool,
+    /// Directory to cache minified `<script>` and `<style>` contents in, so that identical code is only minified once across runs. Created if it doesn't exist.
+    #[structopt(long, parse(from_os_str))]
+    cache_dir: Option<std::path::PathBuf>,
+    /// Never remove whitespace between or around elements and text, only collapse it; whitespace is still trimmed in layout elements.
+    #[structopt(long)]
+    conservative_whitespace: bool,
+    /// Use `display` and `white-space` declarations in the document's CSS to avoid removing whitespace that could be rendered.
+    #[structopt(long)]
+    css_aware_whitespace: bool,
+    /// URL of the document, used to rewrite URLs in attributes to their shortest equivalent form.
+    #[structopt(long)]
+    document_url: Option<String>,
+    /// Rename class names and IDs to short generated names, using and updating the JSON mapping in this file, which is created if it doesn't exist. The file has the form `{"classes": {"original": "mangled"}, "ids": {...}}`.
+    #[structopt(long, parse(from_os_str))]
+    name_map: Option<std::path::PathBuf>,
+    /// When renaming class names and IDs, also rename string literals in `<script>` tags that are exactly a name, or a selector for one like `.name` or `#name`.
+    #[structopt(long)]
+    mangle_names_in_scripts: bool,
+    /// Merge adjacent sibling formatting elements with the same tag name and attributes, like `<b>a</b><b>b</b>`.
+    #[structopt(long)]
+    merge_formatting_elements: bool,
     /// Minify JS in `<script>` tags that have a valid or no `type` attribute value.
     #[structopt(long)]
     minify_js: bool,
     /// Minify CSS in `<style>` tags and `style` attributes.
     #[structopt(long)]
     minify_css: bool,
+    /// ECMAScript version that minified JS must be compatible with; one of `es5`, `es2015` to `es2021`, and `esnext`.
+    #[structopt(long, default_value = "esnext", parse(try_from_str = parse_es_target))]
+    js_target: EsTarget,
+    /// Keep legal comments like `/*! ... */` and those containing `@license` or `@preserve` when minifying JS and CSS.
+    #[structopt(long)]
+    keep_legal_comments: bool,
     /// Ensure all unquoted attribute values in the output do not contain any characters prohibited by the WHATWG specification.
     #[structopt(long)]
     ensure_spec_compliant_unquoted_attribute_values: bool,
+    /// Maximum number of `<script>` and `<style>` contents minified at the same time; 0 uses the number of available CPUs.
+    #[structopt(long, default_value = "0")]
+    esbuild_threads: usize,
+    /// Type of `<script>` tags whose content is HTML and should be minified, replacing the default list of template types like `text/x-template`. Can be provided multiple times.
+    #[structopt(long)]
+    html_script_type: Vec<String>,
     /// Do not omit closing tags when possible.
     #[structopt(long)]
     keep_closing_tags: bool,
     /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes.
     #[structopt(long)]
     keep_html_and_head_opening_tags: bool,
+    /// Keep attributes that have no effect in a category instead of removing them; one of `form-method`, `script-charset`, `script-language`, `script-type`, and `style-type`. Can be provided multiple times.
+    #[structopt(long, parse(try_from_str = parse_redundant_attribute_category))]
+    keep_redundant_attributes: Vec<RedundantAttributeCategory>,
     /// Keep spaces between attributes when possible to conform to HTML standards.
     #[structopt(long)]
     keep_spaces_between_attributes: bool,
     /// Keep all comments.
     #[structopt(long)]
     keep_comments: bool,
+    /// Keep comments whose content starts with this prefix, like `!` for `<!--! ... -->`. Can be provided multiple times.
+    #[structopt(long)]
+    keep_comments_with_prefix: Vec<String>,
+    /// Keep comments whose content matches this regular expression. Can be provided multiple times.
+    #[structopt(long, parse(try_from_str = parse_comment_regex))]
+    keep_comments_matching: Vec<CommentRule>,
+    /// Keep server-side include directives like `<!--#include virtual="..." -->`.
+    #[structopt(long)]
+    keep_server_side_includes: bool,
+    /// Remove elements without attributes that would be empty after whitespace is minified, like `<p></p>`.
+    #[structopt(long)]
+    remove_empty_elements: bool,
+    /// Tag name of an element that can be removed when empty, replacing the default list. Can be provided multiple times.
+    #[structopt(long)]
+    removable_empty_element: Vec<String>,
     /// Remove all bangs.
     #[structopt(long)]
     remove_bangs: bool,
     /// Remove all processing_instructions.
     #[structopt(long)]
     remove_processing_instructions: bool,
+    /// Sort class names in `class` attributes into a document-wide order with the most frequent first, to improve compression.
+    #[structopt(long)]
+    sort_class_names: bool,
+    /// Override how whitespace is minified in the content of elements with a tag name, in the form `tag=policy`, where `policy` is a comma-separated list of any of `collapse`, `destroy_whole`, and `trim`. Can be provided multiple times.
+    #[structopt(long, parse(try_from_str = parse_whitespace_minification_override))]
+    whitespace_minification_override: Vec<(Vec<u8>, WhitespaceMinification)>,
+}
+
+fn parse_whitespace_minification_override(
+    s: &str,
+) -> Result<(Vec<u8>, WhitespaceMinification), String> {
+    let (tag, policy) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `tag=policy`, got `{}`", s))?;
+    let mut min = WhitespaceMinification {
+        collapse: false,
+        destroy_whole: false,
+        trim: false,
+    };
+    for p in policy.split(',').filter(|p| !p.is_empty()) {
+        match p {
+            "collapse" => min.collapse = true,
+            "destroy_whole" => min.destroy_whole = true,
+            "trim" => min.trim = true,
+            _ => return Err(format!("unknown whitespace minification `{}`", p)),
+        };
+    }
+    Ok((tag.to_ascii_lowercase().into_bytes(), min))
+}
+
+fn parse_comment_regex(s: &str) -> Result<CommentRule, String> {
+    CommentRule::regex(s).map_err(|e| e.to_string())
+}
+
+fn parse_es_target(s: &str) -> Result<EsTarget, String> {
+    Ok(match s.to_ascii_lowercase().as_str() {
+        "es5" => EsTarget::ES5,
+        "es2015" | "es6" => EsTarget::ES2015,
+        "es2016" => EsTarget::ES2016,
+        "es2017" => EsTarget::ES2017,
+        "es2018" => EsTarget::ES2018,
+        "es2019" => EsTarget::ES2019,
+        "es2020" => EsTarget::ES2020,
+        "es2021" => EsTarget::ES2021,
+        "esnext" => EsTarget::ESNext,
+        _ => return Err(format!("unknown ES target `{}`", s)),
+    })
+}
+
+fn parse_redundant_attribute_category(s: &str) -> Result<RedundantAttributeCategory, String> {
+    Ok(match s {
+        "form-method" => RedundantAttributeCategory::FormMethod,
+        "script-charset" => RedundantAttributeCategory::ScriptCharset,
+        "script-language" => RedundantAttributeCategory::ScriptLanguage,
+        "script-type" => RedundantAttributeCategory::ScriptType,
+        "style-type" => RedundantAttributeCategory::StyleType,
+        _ => return Err(format!("unknown redundant attribute category `{}`", s)),
+    })
+}
+
+fn parse_name_map(json: &[u8]) -> Result<NameMap, String> {
+    let value: Value = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+    let names = |key: &str| -> Result<_, String> {
+        match value.get(key) {
+            None => Ok(Default::default()),
+            Some(Value::Object(names)) => names
+                .iter()
+                .map(|(k, v)| match v {
+                    Value::String(v) => Ok((k.clone(), v.clone())),
+                    _ => Err(format!("mangled name for `{}` is not a string", k)),
+                })
+                .collect(),
+            Some(_) => Err(format!("`{}` is not an object", key)),
+        }
+    };
+    Ok(NameMap {
+        classes: names("classes")?,
+        ids: names("ids")?,
+    })
+}
+
+fn serialize_name_map(name_map: &NameMap) -> Vec<u8> {
+    // serde_json's map is sorted by key, so the file is stable across runs.
+    let names = |names: &std::collections::HashMap<String, String>| {
+        names
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect::<Map<_, _>>()
+    };
+    let mut json = serde_json::to_vec_pretty(&json!({
+        "classes": names(&name_map.classes),
+        "ids": names(&name_map.ids),
+    }))
+    .unwrap();
+    json.push(b'\n');
+    json
 }
 
 macro_rules! io_expect {
@@ -71,20 +226,94 @@ fn main() {
         src_file.read_to_end(&mut src_code),
         "could not load source code"
     );
-    let out_code = minify(
-        &src_code,
-        &Cfg {
-            ensure_spec_compliant_unquoted_attribute_values: args.ensure_spec_compliant_unquoted_attribute_values,
-            keep_closing_tags: args.keep_closing_tags,
-            keep_comments: args.keep_comments,
-            keep_html_and_head_opening_tags: args.keep_html_and_head_opening_tags,
-            keep_spaces_between_attributes: args.keep_spaces_between_attributes,
-            minify_css: args.minify_css,
-            minify_js: args.minify_js,
-            remove_bangs: args.remove_bangs,
-            remove_processing_instructions: args.remove_processing_instructions,
+    let cfg = Cfg {
+        ascii_only: args.ascii_only,
+        cache: args
+            .cache_dir
+            .map(|dir| Arc::new(MinifyCache::on_disk(dir))),
+        conservative_whitespace: args.conservative_whitespace,
+        css_aware_whitespace: args.css_aware_whitespace,
+        css_options: CssOptions {
+            keep_legal_comments: args.keep_legal_comments,
         },
-    );
+        document_url: args.document_url,
+        ensure_spec_compliant_unquoted_attribute_values: args
+            .ensure_spec_compliant_unquoted_attribute_values,
+        esbuild_threads: args.esbuild_threads,
+        html_script_types: if args.html_script_type.is_empty() {
+            Cfg::new().html_script_types
+        } else {
+            args.html_script_type
+                .into_iter()
+                .map(|t| t.into_bytes())
+                .collect()
+        },
+        js_options: JsOptions {
+            keep_legal_comments: args.keep_legal_comments,
+            target: args.js_target,
+        },
+        keep_closing_tags: args.keep_closing_tags,
+        keep_comments: args.keep_comments,
+        keep_comments_matching: args
+            .keep_comments_with_prefix
+            .into_iter()
+            .map(|p| CommentRule::Prefix(p.into_bytes()))
+            .chain(args.keep_comments_matching)
+            .collect(),
+        keep_html_and_head_opening_tags: args.keep_html_and_head_opening_tags,
+        keep_redundant_attributes: args.keep_redundant_attributes.into_iter().collect(),
+        keep_server_side_includes: args.keep_server_side_includes,
+        keep_spaces_between_attributes: args.keep_spaces_between_attributes,
+        mangle_names_in_scripts: args.mangle_names_in_scripts,
+        merge_formatting_elements: args.merge_formatting_elements,
+        minify_css: args.minify_css,
+        minify_js: args.minify_js,
+        removable_empty_elements: if args.removable_empty_element.is_empty() {
+            Cfg::new().removable_empty_elements
+        } else {
+            args.removable_empty_element
+                .iter()
+                .map(|t| t.to_ascii_lowercase().into_bytes())
+                .collect()
+        },
+        remove_empty_elements: args.remove_empty_elements,
+        remove_bangs: args.remove_bangs,
+        remove_processing_instructions: args.remove_processing_instructions,
+        sort_class_names: args.sort_class_names,
+        whitespace_minification_overrides: args
+            .whitespace_minification_override
+            .into_iter()
+            .collect(),
+    };
+    let (out_code, diagnostics) = match &args.name_map {
+        Some(p) => {
+            let mut name_map = if p.exists() {
+                let json = io_expect!(std::fs::read(p), "could not load name map");
+                io_expect!(parse_name_map(&json), "could not parse name map")
+            } else {
+                NameMap::new()
+            };
+            let res = minify_with_name_map(&src_code, &cfg, &mut name_map);
+            io_expect!(
+                std::fs::write(p, serialize_name_map(&name_map)),
+                "could not save name map"
+            );
+            res
+        }
+        None => minify_with_diagnostics(&src_code, &cfg),
+    };
+    for d in diagnostics {
+        let line = src_code[..d.span.start]
+            .iter()
+            .filter(|&&c| c == b'\n')
+            .count()
+            + 1;
+        eprintln!(
+            "Warning: element at line {}: {}",
+            line,
+            d.diagnostic_type.message()
+        );
+    }
     let mut out_file: Box<dyn Write> = match args.output {
         Some(p) => Box::new(io_expect!(File::create(p), "could not open output file")),
         None => Box::new(stdout()),
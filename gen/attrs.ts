@@ -3,24 +3,167 @@ import { writeFileSync } from "fs";
 import { join } from "path";
 import { RUST_OUT_DIR } from "./_common";
 
-const rsTagAttr = ({
-  redundantIfEmpty,
-  defaultValue,
-  collapseAndTrim,
-  boolean,
-}: {
-  boolean: boolean;
-  redundantIfEmpty: boolean;
-  collapseAndTrim: boolean;
-  defaultValue?: string;
-}) =>
+// Attributes whose value is a single URL, as per https://html.spec.whatwg.org/multipage/indices.html#attributes-3.
+const htmlUrlAttrs = new Map<string, string[]>([
+  ["action", ["form"]],
+  ["cite", ["blockquote", "del", "ins", "q"]],
+  ["data", ["object"]],
+  ["formaction", ["button", "input"]],
+  ["href", ["a", "area", "link"]],
+  ["poster", ["video"]],
+  [
+    "src",
+    [
+      "audio",
+      "embed",
+      "iframe",
+      "img",
+      "input",
+      "script",
+      "source",
+      "track",
+      "video",
+    ],
+  ],
+]);
+
+type RedundantAttrRule = {
+  category:
+    | "FormMethod"
+    | "ScriptCharset"
+    | "ScriptLanguage"
+    | "ScriptType"
+    | "StyleType";
+  tag: string;
+  attr: string;
+  // Lowercase values, or "any" for any value, or "javascriptMimeType" for any JavaScript MIME type.
+  value: string[] | "any" | "javascriptMimeType";
+  condition?:
+    | { withAttr: string }
+    | { withoutAttr: string }
+    | { withAttrToken: [string, string] };
+};
+
+// Attributes that have no effect, in addition to those with default values in the HTML data.
+// Rules are grouped into categories that can be kept via `Cfg`.
+const redundantAttrRules: RedundantAttrRule[] = [
+  // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#attr-fs-method
+  { category: "FormMethod", tag: "form", attr: "method", value: ["get"] },
+  // https://html.spec.whatwg.org/multipage/scripting.html#prepare-the-script-element
+  // The charset is only used to decode external scripts, and the document is always UTF-8.
+  {
+    category: "ScriptCharset",
+    tag: "script",
+    attr: "charset",
+    value: "any",
+    condition: { withoutAttr: "src" },
+  },
+  {
+    category: "ScriptCharset",
+    tag: "script",
+    attr: "charset",
+    value: ["utf-8", "utf8", "unicode-1-1-utf-8"],
+  },
+  // The language is ignored if there's a type, and otherwise is prefixed with "text/" to form the type.
+  {
+    category: "ScriptLanguage",
+    tag: "script",
+    attr: "language",
+    value: "any",
+    condition: { withAttr: "type" },
+  },
+  {
+    category: "ScriptLanguage",
+    tag: "script",
+    attr: "language",
+    value: [
+      "ecmascript",
+      "javascript",
+      "javascript1.0",
+      "javascript1.1",
+      "javascript1.2",
+      "javascript1.3",
+      "javascript1.4",
+      "javascript1.5",
+      "jscript",
+      "livescript",
+      "x-ecmascript",
+      "x-javascript",
+    ],
+  },
+  // Removing the type would make a non-JavaScript language take effect.
+  {
+    category: "ScriptType",
+    tag: "script",
+    attr: "type",
+    value: "javascriptMimeType",
+    condition: { withoutAttr: "language" },
+  },
+  // https://html.spec.whatwg.org/multipage/semantics.html#update-a-style-block
+  { category: "StyleType", tag: "style", attr: "type", value: ["text/css"] },
+  // https://html.spec.whatwg.org/multipage/links.html#link-type-stylesheet
+  {
+    category: "StyleType",
+    tag: "link",
+    attr: "type",
+    value: ["text/css"],
+    condition: { withAttrToken: ["rel", "stylesheet"] },
+  },
+];
+
+const rsRedundantAttrRule = ({
+  category,
+  tag,
+  attr,
+  value,
+  condition,
+}: RedundantAttrRule) =>
+  `  RedundantAttributeRule {
+    category: RedundantAttributeCategory::${category},
+    tag: b"${tag}",
+    attr: b"${attr}",
+    value: ${
+      value == "any"
+        ? "RedundantAttributeValue::Any"
+        : value == "javascriptMimeType"
+        ? "RedundantAttributeValue::JavaScriptMimeType"
+        : `RedundantAttributeValue::OneOf(&[${value
+            .map((v) => `b"${v}"`)
+            .join(", ")}])`
+    },
+    condition: ${
+      !condition
+        ? "RedundantAttributeCondition::Always"
+        : "withAttr" in condition
+        ? `RedundantAttributeCondition::WithAttribute(b"${condition.withAttr}")`
+        : "withoutAttr" in condition
+        ? `RedundantAttributeCondition::WithoutAttribute(b"${condition.withoutAttr}")`
+        : `RedundantAttributeCondition::WithAttributeToken(b"${condition.withAttrToken[0]}", b"${condition.withAttrToken[1]}")`
+    },
+  },`;
+
+const rsTagAttr = (
+  {
+    redundantIfEmpty,
+    defaultValue,
+    collapseAndTrim,
+    boolean,
+  }: {
+    boolean: boolean;
+    redundantIfEmpty: boolean;
+    collapseAndTrim: boolean;
+    defaultValue?: string;
+  },
+  url: boolean
+) =>
   `AttributeMinification { boolean: ${boolean}, redundant_if_empty: ${redundantIfEmpty}, collapse_and_trim: ${collapseAndTrim}, default_value: ${
     defaultValue == undefined ? "None" : `Some(b"${defaultValue}")`
-  } }`;
+  }, url: ${url} }`;
 
 let code = `
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use crate::spec::attr::{RedundantAttributeCategory, RedundantAttributeCondition, RedundantAttributeRule, RedundantAttributeValue};
 use crate::spec::tag::ns::Namespace;
 
 pub struct AttributeMinification {
@@ -28,6 +171,7 @@ pub struct AttributeMinification {
     pub redundant_if_empty: bool,
     pub collapse_and_trim: bool,
     pub default_value: Option<&'static [u8]>,
+    pub url: bool,
 }
 
 pub enum AttrMapEntry {
@@ -86,7 +230,8 @@ ${(["html", "svg"] as const)
         const globalAttr = tagsMap["*"];
         if (globalAttr) {
           return `Some(AttrMapEntry::AllNamespaceElements(${rsTagAttr(
-            globalAttr
+            globalAttr,
+            false
           )}))`;
         }
         const entries = Object.entries(tagsMap);
@@ -97,7 +242,10 @@ ${(["html", "svg"] as const)
 ${entries
   .map(
     ([tagName, tagAttr]) =>
-      `        m.insert(b\"${tagName}\", ${rsTagAttr(tagAttr)});`
+      `        m.insert(b\"${tagName}\", ${rsTagAttr(
+        tagAttr,
+        ns == "html" && (htmlUrlAttrs.get(attr_name) ?? []).includes(tagName)
+      )});`
   )
   .join("\n")}
         AttrMapEntry::SpecificNamespaceElements(m)
@@ -113,6 +261,11 @@ ${entries
   .join("")}
     AttrMap::new(m)
   };
-}`;
+}
+
+pub static REDUNDANT_ATTRIBUTE_RULES: &[RedundantAttributeRule] = &[
+${redundantAttrRules.map(rsRedundantAttrRule).join("\n")}
+];
+`;
 
 writeFileSync(join(RUST_OUT_DIR, "attrs.rs"), code);
@@ -18,6 +18,7 @@ fn build_cfg(
         minify_js: env.get_field(*obj, "minify_js", "Z").unwrap().z().unwrap(),
         remove_bangs: env.get_field(*obj, "remove_bangs", "Z").unwrap().z().unwrap(),
         remove_processing_instructions: env.get_field(*obj, "remove_processing_instructions", "Z").unwrap().z().unwrap(),
+        ..Cfg::new()
     }
 }
 
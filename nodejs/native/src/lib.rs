@@ -23,6 +23,7 @@ pub extern "C" fn ffi_create_cfg(
         minify_js,
         remove_bangs,
         remove_processing_instructions,
+        ..Cfg::new()
     }))
 }
 
@@ -38,6 +38,7 @@ fn minify(
         minify_js,
         remove_bangs,
         remove_processing_instructions,
+        ..Cfg::new()
     });
     Ok(String::from_utf8(out_code).unwrap())
 }
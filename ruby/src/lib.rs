@@ -38,6 +38,7 @@ methods! {
             minify_js: get_cfg_hash_prop!(cfg_hash, "minify_js"),
             remove_bangs: get_cfg_hash_prop!(cfg_hash, "remove_bangs"),
             remove_processing_instructions: get_cfg_hash_prop!(cfg_hash, "remove_processing_instructions"),
+            ..Cfg::new()
         };
 
         let out_code = minify_html_native(&code, cfg);
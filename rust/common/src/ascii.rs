@@ -0,0 +1,80 @@
+use std::str::from_utf8;
+
+use crate::gen::codepoints::WHITESPACE;
+
+// Returns the char starting at `code[0]` and its length in bytes, if `code` starts with a valid UTF-8 sequence.
+fn leading_char(code: &[u8]) -> Option<(char, usize)> {
+    let len = match code.first()? {
+        c if c >> 7 == 0b0 => 1,
+        c if c >> 5 == 0b110 => 2,
+        c if c >> 4 == 0b1110 => 3,
+        c if c >> 3 == 0b11110 => 4,
+        _ => return None,
+    };
+    let c = from_utf8(code.get(..len)?).ok()?.chars().next()?;
+    Some((c, len))
+}
+
+// Copies `code`, but calls `escape` to write every non-ASCII character instead.
+// `escape` is given the output so far, the character, and the byte immediately following it (if any).
+// Bytes that are not part of a valid UTF-8 sequence are copied as is.
+pub fn escape_non_ascii<F: Fn(&mut Vec<u8>, char, Option<u8>)>(code: &[u8], escape: F) -> Vec<u8> {
+    let mut out = Vec::with_capacity(code.len());
+    let mut i = 0;
+    while i < code.len() {
+        let c = code[i];
+        if c.is_ascii() {
+            out.push(c);
+            i += 1;
+            continue;
+        };
+        match leading_char(&code[i..]) {
+            Some((c, len)) => {
+                escape(&mut out, c, code.get(i + len).copied());
+                i += len;
+            }
+            None => {
+                out.push(c);
+                i += 1;
+            }
+        };
+    }
+    out
+}
+
+// Returns true if the last character written to `out` is a backslash that escapes the next character.
+fn ends_with_escaping_backslash(out: &[u8]) -> bool {
+    out.iter().rev().take_while(|&&c| c == b'\\').count() % 2 == 1
+}
+
+// Escapes non-ASCII characters in JS code using `\uXXXX` escapes, writing astral characters as surrogate pairs.
+pub fn escape_js_non_ascii(code: &[u8]) -> Vec<u8> {
+    escape_non_ascii(code, |out, c, _| {
+        // An escaped non-ASCII character is the character itself, so replace the existing escape.
+        if ends_with_escaping_backslash(out) {
+            out.pop();
+        };
+        let mut units = [0u16; 2];
+        for u in c.encode_utf16(&mut units) {
+            out.extend_from_slice(format!("\\u{:04x}", u).as_bytes());
+        }
+    })
+}
+
+// Escapes non-ASCII characters in CSS code using hex escapes.
+pub fn escape_css_non_ascii(code: &[u8]) -> Vec<u8> {
+    escape_non_ascii(code, |out, c, next| {
+        // An escaped non-ASCII character is the character itself, so replace the existing escape.
+        if ends_with_escaping_backslash(out) {
+            out.pop();
+        };
+        out.extend_from_slice(format!("\\{:x}", c as u32).as_bytes());
+        // A hex escape consumes up to six hex digits and a single following whitespace character.
+        if next
+            .filter(|n| n.is_ascii_hexdigit() || WHITESPACE[*n])
+            .is_some()
+        {
+            out.push(b' ');
+        };
+    })
+}
@@ -0,0 +1,146 @@
+#[cfg(feature = "js-esbuild")]
+use {
+    esbuild_rs::{
+        Charset, Format, LegalComments, Loader, SourceMap, Target, TransformOptions,
+        TransformOptionsBuilder,
+    },
+    std::sync::Arc,
+};
+
+/// The ECMAScript version that minified JS must be compatible with.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EsTarget {
+    ES5,
+    ES2015,
+    ES2016,
+    ES2017,
+    ES2018,
+    ES2019,
+    ES2020,
+    ES2021,
+    ESNext,
+}
+
+/// Options for minifying JS using [esbuild](https://esbuild.github.io/api/#transform-api).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsOptions {
+    /// Keep [legal comments](https://esbuild.github.io/api/#legal-comments) like `/*! ... */`
+    /// and comments containing `@license` or `@preserve`.
+    pub keep_legal_comments: bool,
+    /// Only use syntax supported by this version, transforming newer syntax where possible.
+    pub target: EsTarget,
+}
+
+impl Default for JsOptions {
+    fn default() -> JsOptions {
+        JsOptions {
+            keep_legal_comments: false,
+            target: EsTarget::ESNext,
+        }
+    }
+}
+
+/// Options for minifying CSS using [esbuild](https://esbuild.github.io/api/#transform-api).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CssOptions {
+    /// Keep [legal comments](https://esbuild.github.io/api/#legal-comments) like `/*! ... */`
+    /// and comments containing `@license` or `@preserve`.
+    pub keep_legal_comments: bool,
+}
+
+#[cfg(feature = "js-esbuild")]
+fn new_builder(ascii_only: bool, keep_legal_comments: bool) -> TransformOptionsBuilder {
+    let mut builder = TransformOptionsBuilder::new();
+    builder.charset = if ascii_only {
+        Charset::ASCII
+    } else {
+        Charset::UTF8
+    };
+    builder.legal_comments = if keep_legal_comments {
+        LegalComments::Inline
+    } else {
+        LegalComments::None
+    };
+    builder.minify_identifiers = true;
+    builder.minify_syntax = true;
+    builder.minify_whitespace = true;
+    builder.source_map = SourceMap::None;
+    builder
+}
+
+#[cfg(feature = "js-esbuild")]
+impl JsOptions {
+    fn builder(&self, ascii_only: bool) -> TransformOptionsBuilder {
+        let mut builder = new_builder(ascii_only, self.keep_legal_comments);
+        builder.target = match self.target {
+            EsTarget::ES5 => Target::ES5,
+            EsTarget::ES2015 => Target::ES2015,
+            EsTarget::ES2016 => Target::ES2016,
+            EsTarget::ES2017 => Target::ES2017,
+            EsTarget::ES2018 => Target::ES2018,
+            EsTarget::ES2019 => Target::ES2019,
+            EsTarget::ES2020 => Target::ES2020,
+            EsTarget::ES2021 => Target::ES2021,
+            EsTarget::ESNext => Target::ESNext,
+        };
+        builder
+    }
+
+    // `module` indicates that the code is an ES module, where top-level declarations are local to the module.
+    fn transform_options(&self, module: bool, ascii_only: bool) -> Arc<TransformOptions> {
+        let mut builder = self.builder(ascii_only);
+        if module {
+            builder.format = Format::ESModule;
+        };
+        builder.build()
+    }
+
+    // For scripts whose completion value is used, like `javascript:` URLs. Syntax isn't minified, as esbuild drops
+    // unused expressions and rewrites statements like `if (a) b` to `a && b`, which changes the completion value.
+    fn completion_value_transform_options(&self, ascii_only: bool) -> Arc<TransformOptions> {
+        let mut builder = self.builder(ascii_only);
+        builder.minify_syntax = false;
+        builder.build()
+    }
+}
+
+#[cfg(feature = "js-esbuild")]
+impl CssOptions {
+    fn transform_options(&self, ascii_only: bool) -> Arc<TransformOptions> {
+        let mut builder = new_builder(ascii_only, self.keep_legal_comments);
+        builder.loader = Loader::CSS;
+        builder.build()
+    }
+}
+
+// The options passed to esbuild for each kind of code, which are built once per document.
+#[cfg(feature = "js-esbuild")]
+pub struct EsbuildTransformOptions {
+    pub css: Arc<TransformOptions>,
+    pub js: Arc<TransformOptions>,
+    pub js_module: Arc<TransformOptions>,
+    // For `javascript:` URLs, whose completion value must be kept.
+    pub js_url: Arc<TransformOptions>,
+}
+
+#[cfg(feature = "js-esbuild")]
+impl EsbuildTransformOptions {
+    // `ascii_only` escapes non-ASCII characters in the minified code.
+    pub fn new(js: &JsOptions, css: &CssOptions, ascii_only: bool) -> EsbuildTransformOptions {
+        EsbuildTransformOptions {
+            css: css.transform_options(ascii_only),
+            js: js.transform_options(false, ascii_only),
+            js_module: js.transform_options(true, ascii_only),
+            js_url: js.completion_value_transform_options(ascii_only),
+        }
+    }
+
+    // `module` indicates that the code is an ES module, where top-level declarations are local to the module.
+    pub fn js(&self, module: bool) -> &Arc<TransformOptions> {
+        if module {
+            &self.js_module
+        } else {
+            &self.js
+        }
+    }
+}
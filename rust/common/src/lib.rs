@@ -1,3 +1,5 @@
+pub mod ascii;
+pub mod esbuild;
 pub mod gen;
 pub mod pattern;
 pub mod spec;
@@ -0,0 +1,84 @@
+use crate::gen::attrs::REDUNDANT_ATTRIBUTE_RULES;
+use crate::gen::codepoints::WHITESPACE;
+use crate::spec::script::JAVASCRIPT_MIME_TYPES;
+
+// Categories of attributes that have no effect, which can each be kept when minifying.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RedundantAttributeCategory {
+    // `method="get"` on `<form>`.
+    FormMethod,
+    // `charset` on `<script>` when it has no effect or is UTF-8, the encoding of the document.
+    ScriptCharset,
+    // `language` on `<script>` when it's ignored or indicates JavaScript.
+    ScriptLanguage,
+    // `type` on `<script>` when it's a JavaScript MIME type.
+    ScriptType,
+    // `type="text/css"` on `<style>` and `<link rel=stylesheet>`.
+    StyleType,
+}
+
+pub enum RedundantAttributeValue {
+    Any,
+    OneOf(&'static [&'static [u8]]),
+    JavaScriptMimeType,
+}
+
+pub enum RedundantAttributeCondition {
+    Always,
+    WithAttribute(&'static [u8]),
+    WithoutAttribute(&'static [u8]),
+    // The attribute's value contains this token, matched ASCII case-insensitively.
+    WithAttributeToken(&'static [u8], &'static [u8]),
+}
+
+pub struct RedundantAttributeRule {
+    pub category: RedundantAttributeCategory,
+    pub tag: &'static [u8],
+    pub attr: &'static [u8],
+    pub value: RedundantAttributeValue,
+    pub condition: RedundantAttributeCondition,
+}
+
+fn trim(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|&c| !WHITESPACE[c]).unwrap_or(s.len());
+    let end = s.iter().rposition(|&c| !WHITESPACE[c]).map_or(start, |i| i + 1);
+    &s[start..end]
+}
+
+// Whether an attribute on an HTML element has no effect according to the rules in `REDUNDANT_ATTRIBUTE_RULES`.
+// Values are matched ASCII case-insensitively after trimming whitespace. `get_attr` returns the value of another
+// attribute on the same element; conditions are checked against the original attributes, so rules must remain
+// correct if other redundant attributes are removed.
+pub fn is_redundant_attribute<'a>(
+    keep: impl Fn(RedundantAttributeCategory) -> bool,
+    tag: &[u8],
+    name: &[u8],
+    value: &[u8],
+    get_attr: impl Fn(&[u8]) -> Option<&'a [u8]>,
+) -> bool {
+    let value = trim(value).to_ascii_lowercase();
+    REDUNDANT_ATTRIBUTE_RULES.iter().any(|r| {
+        r.tag == tag
+            && r.attr == name
+            && !keep(r.category)
+            && match r.value {
+                RedundantAttributeValue::Any => true,
+                RedundantAttributeValue::OneOf(values) => values.contains(&value.as_slice()),
+                RedundantAttributeValue::JavaScriptMimeType => {
+                    JAVASCRIPT_MIME_TYPES.contains(value.as_slice())
+                }
+            }
+            && match r.condition {
+                RedundantAttributeCondition::Always => true,
+                RedundantAttributeCondition::WithAttribute(a) => get_attr(a).is_some(),
+                RedundantAttributeCondition::WithoutAttribute(a) => get_attr(a).is_none(),
+                RedundantAttributeCondition::WithAttributeToken(a, token) => {
+                    get_attr(a).filter(|v| {
+                        v.split(|&c| WHITESPACE[c])
+                            .any(|t| t.eq_ignore_ascii_case(token))
+                    })
+                    .is_some()
+                }
+            }
+    })
+}
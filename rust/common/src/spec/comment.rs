@@ -0,0 +1,51 @@
+// The kinds of conditional comments, which are interpreted by older versions of Internet Explorer.
+// See https://learn.microsoft.com/en-us/previous-versions/windows/internet-explorer/ie-developer/compatibility/ms537512(v=vs.85).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ConditionalComment {
+    // A comment like `<!--[if IE]>...<![endif]-->` whose content is HTML that's only rendered when the condition is met.
+    // `opening_len` is the length of the `[if IE]>` at the start of the comment's code.
+    DownlevelHidden { opening_len: usize },
+    // The `<!--[if !IE]><!-->` that starts content that's rendered by other browsers.
+    DownlevelRevealedStart,
+    // The `<!--<![endif]-->` that ends content started by a `DownlevelRevealedStart`.
+    DownlevelRevealedEnd,
+}
+
+const ENDIF: &[u8] = b"<![endif]";
+
+// Returns the kind of conditional comment, given the comment's code between `<!--` and `-->`.
+pub fn get_conditional_comment(code: &[u8]) -> Option<ConditionalComment> {
+    if code == ENDIF {
+        return Some(ConditionalComment::DownlevelRevealedEnd);
+    };
+    if !code.starts_with(b"[if ") {
+        return None;
+    };
+    let opening_len = code.windows(2).position(|w| w == b"]>")? + 2;
+    if code[opening_len..] == *b"<!" {
+        Some(ConditionalComment::DownlevelRevealedStart)
+    } else if code[opening_len..].ends_with(ENDIF) {
+        Some(ConditionalComment::DownlevelHidden { opening_len })
+    } else {
+        None
+    }
+}
+
+// Whether a bang's code between `<!` and `>` is the start or end of a downlevel-revealed conditional comment in the
+// `<![if !IE]>...<![endif]>` form.
+pub fn is_conditional_bang(code: &[u8]) -> bool {
+    code == b"[endif]" || (code.starts_with(b"[if ") && code.ends_with(b"]"))
+}
+
+// Whether a comment's code between `<!--` and `-->` is a server-side include directive like `#include virtual="..."`.
+// See https://httpd.apache.org/docs/current/howto/ssi.html.
+pub fn is_server_side_include(code: &[u8]) -> bool {
+    matches!(code, [b'#', c, ..] if c.is_ascii_alphabetic())
+}
+
+// Whether a comment's code between `<!--` and `-->` is an Edge Side Includes comment like `esi <p>...</p>`, whose
+// content after the `esi` is HTML that's used when the document is processed by an ESI processor.
+// See https://www.w3.org/TR/esi-lang/.
+pub fn is_esi_comment(code: &[u8]) -> bool {
+    matches!(code, [b'e', b's', b'i', c, ..] if c.is_ascii_whitespace())
+}
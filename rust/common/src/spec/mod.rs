@@ -1,2 +1,4 @@
+pub mod attr;
+pub mod comment;
 pub mod script;
 pub mod tag;
@@ -23,3 +23,22 @@ lazy_static! {
         s
     };
 }
+
+lazy_static! {
+    // Script types whose content is JSON. See https://html.spec.whatwg.org/multipage/scripting.html#attr-script-type.
+    pub static ref JSON_SCRIPT_TYPES: HashSet<&'static [u8]> = {
+        let mut s = HashSet::<&'static [u8]>::new();
+        s.insert(b"application/json");
+        s.insert(b"application/ld+json");
+        s.insert(b"importmap");
+        s.insert(b"speculationrules");
+        s
+    };
+}
+
+// Whether a `<script>` tag's `type` attribute value indicates that it contains an ES module, which is always in strict
+// mode and can use `import`, `export`, and top-level `await`. See
+// https://html.spec.whatwg.org/multipage/scripting.html#attr-script-type.
+pub fn is_module_script_type(value: &[u8]) -> bool {
+    value.eq_ignore_ascii_case(b"module")
+}
@@ -0,0 +1,5 @@
+// Whether a tag name is an Edge Side Includes element like `esi:include`, which is processed and replaced before the
+// document reaches the browser. See https://www.w3.org/TR/esi-lang/.
+pub fn is_esi_tag(name: &[u8]) -> bool {
+    name.starts_with(b"esi:")
+}
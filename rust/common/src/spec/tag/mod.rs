@@ -1,3 +1,4 @@
+pub mod esi;
 pub mod ns;
 pub mod omission;
 pub mod void;
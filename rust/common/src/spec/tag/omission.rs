@@ -1,8 +1,11 @@
 use lazy_static::lazy_static;
 use std::collections::{HashMap, HashSet};
 
+use crate::gen::codepoints::{TAG_NAME_CHAR, WHITESPACE};
+
 // Rules sourced from https://html.spec.whatwg.org/multipage/syntax.html#syntax-tag-omission.
-// TODO Opening tags
+// The opening tags of `<html>` and `<head>` are omitted by the minifiers whenever they have no attributes, unless
+// configured otherwise.
 
 enum ClosingTagOmissionRuleIfLast {
     // Closing tag can always be omitted if it's the last node of its parent's children.
@@ -293,3 +296,47 @@ pub fn can_omit_as_before(before: &[u8], after: &[u8]) -> bool {
         .filter(|r| r.followed_by.contains(after))
         .is_some()
 }
+
+// Returns the lowercase tag name if `content` starts with an opening tag.
+fn first_element_name(content: &[u8]) -> Option<Vec<u8>> {
+    let rest = content.strip_prefix(b"<")?;
+    let len = rest.iter().take_while(|&&c| TAG_NAME_CHAR[c]).count();
+    if len == 0 {
+        return None;
+    };
+    Some(rest[..len].to_ascii_lowercase())
+}
+
+// Whether the opening tag of a `<body>`, `<colgroup>`, or `<tbody>` element without attributes can be omitted.
+// `content` is the element's minified content, and `previous_sibling_with_omitted_closing_tag` is the tag name of the
+// immediately preceding sibling element if its closing tag was omitted, or an empty slice otherwise.
+pub fn can_omit_opening_tag(
+    tag_name: &[u8],
+    previous_sibling_with_omitted_closing_tag: &[u8],
+    content: &[u8],
+) -> bool {
+    let first = first_element_name(content);
+    match tag_name {
+        b"body" => match content.first() {
+            None => true,
+            Some(&c) if WHITESPACE[c] => false,
+            _ if content.starts_with(b"<!--") => false,
+            _ => !matches!(
+                first.as_deref(),
+                Some(b"meta" | b"noscript" | b"link" | b"script" | b"style" | b"template")
+            ),
+        },
+        b"colgroup" => {
+            first.as_deref() == Some(b"col")
+                && previous_sibling_with_omitted_closing_tag != b"colgroup"
+        }
+        b"tbody" => {
+            first.as_deref() == Some(b"tr")
+                && !matches!(
+                    previous_sibling_with_omitted_closing_tag,
+                    b"tbody" | b"thead" | b"tfoot"
+                )
+        }
+        _ => false,
+    }
+}
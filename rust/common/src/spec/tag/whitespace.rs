@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use lazy_static::lazy_static;
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct WhitespaceMinification {
     pub collapse: bool,
     pub destroy_whole: bool,
@@ -166,15 +167,18 @@ lazy_static! {
     };
 }
 
-pub fn get_whitespace_minification_for_tag(
+pub fn get_whitespace_minification_for_tag<'a>(
+    overrides: &'a HashMap<Vec<u8>, WhitespaceMinification>,
     // Use empty slice if root.
     tag_name: &[u8],
     descendant_of_pre: bool,
-) -> &'static WhitespaceMinification {
+) -> &'a WhitespaceMinification {
     if descendant_of_pre {
         WHITESPACE_SENSITIVE
     } else if tag_name.is_empty() {
         ROOT
+    } else if let Some(o) = overrides.get(tag_name) {
+        o
     } else {
         TAG_WHITESPACE_MINIFICATION
             .get(tag_name)
@@ -1,6 +1,5 @@
 fn eval_with_cfg(src: &'static [u8], expected: &'static [u8], cfg: &super::Cfg) {
-    let mut code = src.to_vec();
-    let min = super::minify(&mut code, cfg);
+    let min = super::minify(src, cfg);
     assert_eq!(
         std::str::from_utf8(&min).unwrap(),
         std::str::from_utf8(expected).unwrap(),
@@ -11,21 +10,21 @@ fn eval(src: &'static [u8], expected: &'static [u8]) {
     eval_with_cfg(src, expected, &super::Cfg::new());
 }
 
-fn eval_with_keep_html_head(src: &'static [u8], expected: &'static [u8]) -> () {
+fn eval_with_keep_html_head(src: &'static [u8], expected: &'static [u8]) {
     let mut cfg = super::Cfg::new();
     cfg.keep_html_and_head_opening_tags = true;
     eval_with_cfg(src, expected, &cfg);
 }
 
 #[cfg(feature = "js-esbuild")]
-fn eval_with_js_min(src: &'static [u8], expected: &'static [u8]) -> () {
+fn eval_with_js_min(src: &'static [u8], expected: &'static [u8]) {
     let mut cfg = super::Cfg::new();
     cfg.minify_js = true;
     eval_with_cfg(src, expected, &cfg);
 }
 
 #[cfg(feature = "js-esbuild")]
-fn eval_with_css_min(src: &'static [u8], expected: &'static [u8]) -> () {
+fn eval_with_css_min(src: &'static [u8], expected: &'static [u8]) {
     let mut cfg = super::Cfg::new();
     cfg.minify_css = true;
     eval_with_cfg(src, expected, &cfg);
@@ -101,21 +100,33 @@ fn test_no_whitespace_minification() {
 }
 
 #[test]
-fn test_parsing_extra_head_tag() {
-    // Extra `<head>` in `<label>` should be dropped, so whitespace around `<head>` should be joined and therefore trimmed due to `<label>` whitespace rules.
-    eval_with_keep_html_head(
-        b"<html><head><meta><head><link><head><body><label>  <pre> </pre> <head>  </label>",
-        b"<html><head><meta><link><body><label><pre> </pre></label>",
+fn test_whitespace_minification_overrides() {
+    let mut cfg = super::Cfg::new();
+    cfg.whitespace_minification_overrides.insert(
+        b"ui-stack".to_vec(),
+        crate::WhitespaceMinification {
+            collapse: true,
+            destroy_whole: true,
+            trim: true,
+        },
+    );
+    cfg.whitespace_minification_overrides.insert(
+        b"x-code".to_vec(),
+        crate::WhitespaceMinification {
+            collapse: false,
+            destroy_whole: false,
+            trim: true,
+        },
     );
-    // Same as above except it's a `</head>`, which should get reinterpreted as a `<head>`.
-    eval_with_keep_html_head(
-        b"<html><head><meta><head><link><head><body><label>  <pre> </pre> </head>  </label>",
-        b"<html><head><meta><link><body><label><pre> </pre></label>",
+    eval_with_cfg(
+        b"<ui-stack> <p>a</p> <p>b</p> </ui-stack>",
+        b"<ui-stack><p>a<p>b</ui-stack>",
+        &cfg,
     );
-    // `<head>` gets implicitly closed by `<body>`, so any following `</head>` should be ignored. (They should be anyway, since `</head>` would not be a valid closing tag.)
-    eval_with_keep_html_head(
-        b"<html><head><body><label> </head> </label>",
-        b"<html><head><body><label></label>",
+    eval_with_cfg(
+        b"<x-code> \n a \n <b>b</b>\t\t<b>c</b> \n </x-code>",
+        b"<x-code>a \n <b>b</b>\t\t<b>c</b></x-code>",
+        &cfg,
     );
 }
 
@@ -146,36 +157,22 @@ fn test_parsing_with_omitted_tags() {
     eval_with_keep_html_head(b"<rt>", b"<rt>");
     eval_with_keep_html_head(b"<rt><rp>1</rp><div></div>", b"<rt><rp>1</rp><div></div>");
     eval_with_keep_html_head(b"<div><rt></div>", b"<div><rt></div>");
-    eval_with_keep_html_head(b"<html><head><body>", b"<html><head><body>");
-    eval_with_keep_html_head(b"<html><head><body>", b"<html><head><body>");
+    eval_with_keep_html_head(b"<html><head><body lang=en>", b"<html><head><body lang=en>");
     // Tag names should be case insensitive.
     eval_with_keep_html_head(b"<rt>", b"<rt>");
 }
 
-#[test]
-fn test_unmatched_closing_tag() {
-    eval_with_keep_html_head(b"Hello</p>Goodbye", b"Hello<p>Goodbye");
-    eval_with_keep_html_head(b"Hello<br></br>Goodbye", b"Hello<br>Goodbye");
-    eval_with_keep_html_head(b"<div>Hello</p>Goodbye", b"<div>Hello<p>Goodbye");
-    eval_with_keep_html_head(b"<ul><li>a</p>", b"<ul><li>a<p>");
-    eval_with_keep_html_head(b"<ul><li><rt>a</p>", b"<ul><li><rt>a<p>");
-    eval_with_keep_html_head(
-        b"<html><head><body><ul><li><rt>a</p>",
-        b"<html><head><body><ul><li><rt>a<p>",
-    );
-}
-
 #[test]
 fn test_removal_of_html_and_head_opening_tags() {
     // Even though `<head>` is dropped, it's still parsed, so its content is still subject to `<head>` whitespace minification rules.
     eval(
-        b"<!DOCTYPE html><html><head>  <meta> <body>",
-        b"<!DOCTYPE html><meta><body>",
+        b"<!DOCTYPE html><html><head>  <meta> <body lang=en>",
+        b"<!DOCTYPE html><meta><body lang=en>",
     );
     // The tag should not be dropped if it has attributes.
     eval(
-        b"<!DOCTYPE html><html lang=en><head>  <meta> <body>",
-        b"<!DOCTYPE html><html lang=en><meta><body>",
+        b"<!DOCTYPE html><html lang=en><head>  <meta> <body lang=en>",
+        b"<!DOCTYPE html><html lang=en><meta><body lang=en>",
     );
 }
 
@@ -197,11 +194,11 @@ fn test_removal_of_optional_tags() {
             <head>
             </head>
 
-            <body>
+            <body lang=en>
             </body>
         </html>
     "#,
-        b"<html><head><body>",
+        b"<html><head><body lang=en>",
     );
     // Tag names should be case insensitive.
     eval_with_keep_html_head(b"<RT></rt>", b"<rt>");
@@ -218,6 +215,20 @@ fn test_removal_of_optional_closing_p_tag() {
     );
 }
 
+#[test]
+fn test_keep_closing_tags() {
+    let mut cfg = super::Cfg::new();
+    cfg.keep_closing_tags = true;
+    eval_with_cfg(
+        b"<ul><li>1</li><li>2</li></ul>",
+        b"<ul><li>1</li><li>2</li></ul>",
+        &cfg,
+    );
+    eval_with_cfg(b"<p>a</p>", b"<p>a</p>", &cfg);
+    // Closing tags that are already omitted are not added.
+    eval_with_cfg(b"<ul><li>1<li>2</ul>", b"<ul><li>1<li>2</ul>", &cfg);
+}
+
 #[test]
 fn test_attr_double_quoted_value_minification() {
     eval(b"<a b=\" hello \"></a>", b"<a b=\" hello \"></a>");
@@ -254,9 +265,9 @@ fn test_attr_unquoted_value_minification() {
 fn test_attr_whatwg_unquoted_value_minification() {
     let mut cfg = super::Cfg::new();
     cfg.ensure_spec_compliant_unquoted_attribute_values = true;
-    eval_with_cfg(b"<a b==></a>", br#"<a b="="></a>"#, &cfg);
+    eval_with_cfg(br#"<a b="="></a>"#, br#"<a b="="></a>"#, &cfg);
     eval_with_cfg(
-        br#"<a b=`'"<<==/`/></a>"#,
+        br#"<a b="`'&#34<<==/`/"></a>"#,
         br#"<a b="`'&#34<<==/`/"></a>"#,
         &cfg,
     );
@@ -397,6 +408,27 @@ fn test_space_between_attrs_minification() {
     eval(b"<div a=\"a\"b=\"b\"></div>", b"<div a=a b=b></div>");
 }
 
+#[test]
+fn test_keep_spaces_between_attrs() {
+    let mut cfg = super::Cfg::new();
+    cfg.keep_spaces_between_attributes = true;
+    eval_with_cfg(
+        b"<div a=\" \" b=\" \"></div>",
+        b"<div a=\" \" b=\" \"></div>",
+        &cfg,
+    );
+    eval_with_cfg(
+        b"<div a=' ' b=\" \"></div>",
+        b"<div a=\" \" b=\" \"></div>",
+        &cfg,
+    );
+    eval_with_cfg(
+        b"<div a=\"1\" b=\" \"></div>",
+        b"<div a=1 b=\" \"></div>",
+        &cfg,
+    );
+}
+
 #[test]
 fn test_hexadecimal_entity_decoding() {
     eval(b"&#x2E", b".");
@@ -552,12 +584,81 @@ fn test_comments_removal() {
     );
 }
 
+#[test]
+fn test_keep_comments() {
+    let mut cfg = super::Cfg::new();
+    cfg.keep_comments = true;
+    eval_with_cfg(
+        b"<pre>a <!-- akd--sj\n <!-- \t\0f--ajk--df->lafj -->  b</pre>",
+        b"<pre>a <!-- akd--sj\n <!-- \t\0f--ajk--df->lafj -->  b</pre>",
+        &cfg,
+    );
+    eval_with_cfg(b"<p>a <!-- b -->  c</p>", b"<p>a <!-- b --> c", &cfg);
+}
+
+#[test]
+fn test_downlevel_hidden_conditional_comments() {
+    eval(
+        b"<div><!--[if IE]>  <p class=\"ie\">  Upgrade  your  browser  </p>  <![endif]--></div>",
+        b"<div><!--[if IE]><p class=ie>Upgrade your browser</p><![endif]--></div>",
+    );
+    eval(
+        b"<!--[if lt IE 9]> <b> a </b> <script src=\"html5shiv.js\"></script><![endif]-->",
+        b"<!--[if lt IE 9]><b> a </b><script src=html5shiv.js></script><![endif]-->",
+    );
+    eval(
+        b"<!--[if IE]><p>a</p><![endif]-->",
+        b"<!--[if IE]><p>a</p><![endif]-->",
+    );
+    // The HTML is left as is if minifying it would end the comment early.
+    eval(
+        b"<!--[if IE]> <p>a--&gt;b</p> <![endif]--><p>x",
+        b"<!--[if IE]> <p>a--&gt;b</p> <![endif]--><p>x",
+    );
+}
+
+#[test]
+fn test_downlevel_revealed_conditional_comments() {
+    eval(
+        b"<!--[if !IE]><!--> <b>Not IE</b> <!--<![endif]-->",
+        b"<!--[if !IE]><!--><b>Not IE</b><!--<![endif]-->",
+    );
+}
+
+#[test]
+fn test_malformed_conditional_comments_removal() {
+    eval(b"<p>a<!-- [if IE] -->b<!--[if IE]-->", b"<p>ab");
+    eval(b"<!--[if IE]-->x<!--<![endif]--->", b"x");
+}
+
 #[test]
 fn test_processing_instructions() {
     eval(b"<?php hello??? >>  ?>", b"<?php hello??? >>  ?>");
     eval(b"av<?xml 1.0 ?>g", b"av<?xml 1.0 ?>g");
 }
 
+#[test]
+fn test_processing_instructions_removal() {
+    let mut cfg = super::Cfg::new();
+    cfg.remove_processing_instructions = true;
+    eval_with_cfg(b"<?php hello??? >>  ?>", b"", &cfg);
+    eval_with_cfg(b"av<?xml 1.0 ?>g", b"avg", &cfg);
+}
+
+#[test]
+fn test_bangs() {
+    eval(b"<!doctype html><p>a", b"<!doctype html><p>a");
+    let mut cfg = super::Cfg::new();
+    cfg.remove_bangs = true;
+    eval_with_cfg(b"<!doctype html><p>a", b"<p>a", &cfg);
+    // Conditional comments are always kept.
+    eval_with_cfg(
+        b"<![if !IE]><p>a</p><![endif]>",
+        b"<![if !IE]><p>a<![endif]>",
+        &cfg,
+    );
+}
+
 #[cfg(feature = "js-esbuild")]
 #[test]
 fn test_js_minification() {
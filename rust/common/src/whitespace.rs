@@ -37,6 +37,32 @@ pub fn collapse_whitespace(val: &mut Vec<u8>) {
     val.truncate(write);
 }
 
+// Like `collapse_whitespace`, but collapses a sequence containing a line break to a single line break, so that the
+// lines of the text are kept.
+pub fn collapse_whitespace_keeping_line_breaks(val: &mut Vec<u8>) {
+    let mut write = 0;
+    let mut in_whitespace = false;
+    for i in 0..val.len() {
+        let c = val[i];
+        if WHITESPACE[c] {
+            let is_line_break = c == b'\n' || c == b'\r';
+            if in_whitespace {
+                if is_line_break {
+                    val[write - 1] = b'\n';
+                };
+                continue;
+            };
+            in_whitespace = true;
+            val[write] = if is_line_break { b'\n' } else { b' ' };
+        } else {
+            in_whitespace = false;
+            val[write] = c;
+        };
+        write += 1;
+    }
+    val.truncate(write);
+}
+
 pub fn is_all_whitespace(val: &[u8]) -> bool {
     for &c in val {
         if !WHITESPACE[c] {
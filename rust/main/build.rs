@@ -0,0 +1,17 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Exposes the exact version of `esbuild-rs` declared in Cargo.toml as `ESBUILD_VERSION`, so that the minify cache's
+// keys change whenever esbuild is upgraded without the version having to be kept in sync by hand.
+fn main() {
+    let manifest_path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    let version = manifest
+        .lines()
+        .find_map(|l| l.strip_prefix("esbuild-rs = { version = \"="))
+        .and_then(|l| l.split('"').next())
+        .expect("esbuild-rs must be pinned to an exact version in Cargo.toml");
+    println!("cargo:rustc-env=ESBUILD_VERSION={}", version);
+}
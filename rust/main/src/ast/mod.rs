@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::ops::Range;
 use std::str::from_utf8;
 
 use minify_html_common::spec::tag::ns::Namespace;
@@ -17,6 +18,9 @@ pub enum ScriptOrStyleLang {
     CSS,
     Data,
     JS,
+    // An ES module, where top-level declarations are local to the module.
+    JSModule,
+    Json,
 }
 
 // Derive Eq for testing.
@@ -31,6 +35,8 @@ pub enum NodeData {
         code: Vec<u8>,
         // If the source unexpectedly ended before `-->`, we can't add it, as otherwise output could be longer than source.
         ended: bool,
+        // The position of the comment in the source, including `<!--` and `-->`.
+        span: Range<usize>,
     },
     Element {
         attributes: HashMap<Vec<u8>, Vec<u8>>,
@@ -43,6 +49,8 @@ pub enum NodeData {
         // If the next text or element sibling is an element, this will be set to its tag name.
         // Otherwise, this will be empty. It should be empty on creation.
         next_sibling_element_name: Vec<u8>,
+        // The position of the element in the source, from the start of its opening tag to the end of its closing tag.
+        span: Range<usize>,
     },
     Instruction {
         code: Vec<u8>,
@@ -53,6 +61,8 @@ pub enum NodeData {
     ScriptOrStyleContent {
         code: Vec<u8>,
         lang: ScriptOrStyleLang,
+        // The position of the code in the source.
+        span: Range<usize>,
     },
     Text {
         value: Vec<u8>,
@@ -71,10 +81,11 @@ impl Debug for NodeData {
                 .field("code", &from_utf8(code).unwrap().to_string())
                 .field("ended", ended)
                 .finish(),
-            NodeData::Comment { code, ended } => f
+            NodeData::Comment { code, ended, span } => f
                 .debug_struct("Comment")
                 .field("code", &from_utf8(code).unwrap().to_string())
                 .field("ended", ended)
+                .field("span", span)
                 .finish(),
             NodeData::Element {
                 attributes,
@@ -83,6 +94,7 @@ impl Debug for NodeData {
                 name,
                 namespace,
                 next_sibling_element_name,
+                span,
             } => f
                 .debug_struct("Element")
                 .field("tag", &{
@@ -98,16 +110,18 @@ impl Debug for NodeData {
                     "next_sibling_element_name",
                     &from_utf8(next_sibling_element_name).unwrap().to_string(),
                 )
+                .field("span", span)
                 .finish(),
             NodeData::Instruction { code, ended } => f
                 .debug_struct("Instruction")
                 .field("code", &from_utf8(code).unwrap().to_string())
                 .field("ended", ended)
                 .finish(),
-            NodeData::ScriptOrStyleContent { code, lang } => f
+            NodeData::ScriptOrStyleContent { code, lang, span } => f
                 .debug_struct("ScriptOrStyleContent")
                 .field("code", &from_utf8(code).unwrap().to_string())
                 .field("lang", lang)
+                .field("span", span)
                 .finish(),
             NodeData::Text { value } => f.write_str(str(value)),
         }
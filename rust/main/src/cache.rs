@@ -0,0 +1,169 @@
+// The cache is only used when minifying using esbuild.
+#![cfg_attr(not(feature = "js-esbuild"), allow(dead_code))]
+
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::fs;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// The exact version of `esbuild-rs` pinned in Cargo.toml, which is set by build.rs.
+const ESBUILD_VERSION: &str = env!("ESBUILD_VERSION");
+
+// The most minified code kept in memory, in bytes. The oldest entries are removed first when it's exceeded.
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+struct Memory {
+    // Keyed by a hash of the key, so the code doesn't need to be kept.
+    entries: HashMap<u128, Vec<u8>>,
+    // Hashes in the order they were inserted.
+    order: VecDeque<u128>,
+    size: usize,
+}
+
+impl Memory {
+    fn insert(&mut self, hash: u128, min: Vec<u8>) {
+        if min.len() > MAX_MEMORY_SIZE || self.entries.contains_key(&hash) {
+            return;
+        };
+        while self.size + min.len() > MAX_MEMORY_SIZE {
+            let oldest = self.order.pop_front().unwrap();
+            self.size -= self.entries.remove(&oldest).unwrap().len();
+        }
+        self.size += min.len();
+        self.order.push_back(hash);
+        self.entries.insert(hash, min);
+    }
+}
+
+/// A cache of minified `<script>` and `<style>` contents, so that identical code with the same
+/// options is only minified once. The same cache can be shared across calls to
+/// [`minify`](crate::minify) and across threads by setting [`Cfg::cache`](crate::Cfg::cache).
+///
+/// Code is looked up by its content, language, minification options, and the versions of this
+/// crate and esbuild. Up to 64 MiB of minified code is kept in memory, after which the oldest
+/// entries are removed. A cache on disk can also be shared across processes, such as separate runs
+/// of the CLI.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, MinifyCache};
+/// use std::sync::Arc;
+///
+/// let mut cfg = Cfg::new();
+/// cfg.minify_js = true;
+/// cfg.cache = Some(Arc::new(MinifyCache::in_memory()));
+/// ```
+#[derive(Debug)]
+pub struct MinifyCache {
+    memory: Mutex<Memory>,
+    // Randomly seeded so that code can't be crafted to have the same hash as other code.
+    hash_states: [RandomState; 2],
+    dir: Option<PathBuf>,
+}
+
+impl MinifyCache {
+    /// Creates a cache that is kept in memory.
+    pub fn in_memory() -> MinifyCache {
+        MinifyCache {
+            memory: Mutex::default(),
+            hash_states: Default::default(),
+            dir: None,
+        }
+    }
+
+    /// Creates a cache that is also saved to files in `dir`, which is created if it doesn't exist.
+    /// Files that can't be read or written are ignored.
+    pub fn on_disk<P: Into<PathBuf>>(dir: P) -> MinifyCache {
+        MinifyCache {
+            memory: Mutex::default(),
+            hash_states: Default::default(),
+            dir: Some(dir.into()),
+        }
+    }
+
+    // Builds the key for code of a kind like `js` minified with some options.
+    pub(crate) fn key(kind: &str, options: &dyn Debug, code: &[u8]) -> Vec<u8> {
+        let mut key = format!(
+            "{} {} {} {:?}\n",
+            env!("CARGO_PKG_VERSION"),
+            ESBUILD_VERSION,
+            kind,
+            options
+        )
+        .into_bytes();
+        key.extend_from_slice(code);
+        key
+    }
+
+    fn memory_hash(&self, key: &[u8]) -> u128 {
+        let [a, b] = &self.hash_states;
+        ((a.hash_one(key) as u128) << 64) | b.hash_one(key) as u128
+    }
+
+    // The hash may differ between Rust versions, which only causes cache misses as the key is also saved in the file.
+    fn file_path(&self, key: &[u8]) -> Option<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}", hasher.finish())))
+    }
+
+    pub(crate) fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let hash = self.memory_hash(key);
+        if let Some(min) = self.memory.lock().unwrap().entries.get(&hash) {
+            return Some(min.clone());
+        };
+        // Files contain the key's length as 8 little-endian bytes, the key, and then the minified code.
+        let file = fs::read(self.file_path(key)?).ok()?;
+        let key_len = u64::from_le_bytes(file.get(..8)?.try_into().ok()?) as usize;
+        if file.get(8..)?.get(..key_len)? != key {
+            return None;
+        };
+        let min = file[8 + key_len..].to_vec();
+        self.memory.lock().unwrap().insert(hash, min.clone());
+        Some(min)
+    }
+
+    pub(crate) fn insert(&self, key: Vec<u8>, min: Vec<u8>) {
+        if let Some(path) = self.file_path(&key) {
+            let mut file = Vec::with_capacity(8 + key.len() + min.len());
+            file.extend_from_slice(&(key.len() as u64).to_le_bytes());
+            file.extend_from_slice(&key);
+            file.extend_from_slice(&min);
+            // Write to a temporary file first so other processes never read a partially written file.
+            let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+            let _ = fs::create_dir_all(path.parent().unwrap())
+                .and_then(|_| fs::write(&tmp, &file))
+                .and_then(|_| fs::rename(&tmp, &path));
+        };
+        let hash = self.memory_hash(&key);
+        self.memory.lock().unwrap().insert(hash, min);
+    }
+}
+
+// Returns the cached result for the key built by `key` if there's a cache, or otherwise minifies the code using
+// `minify` and caches the result if it succeeded.
+pub(crate) fn cached<E>(
+    cache: Option<&MinifyCache>,
+    key: impl FnOnce() -> Vec<u8>,
+    minify: impl FnOnce() -> Result<Vec<u8>, E>,
+) -> Result<Vec<u8>, E> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return minify(),
+    };
+    let key = key();
+    if let Some(min) = cache.get(&key) {
+        return Ok(min);
+    };
+    let min = minify()?;
+    cache.insert(key, min.clone());
+    Ok(min)
+}
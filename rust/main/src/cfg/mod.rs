@@ -1,16 +1,86 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::cache::MinifyCache;
+use crate::comment_rule::CommentRule;
+use crate::minify::empty::default_removable_empty_elements;
+use crate::minify::script_html::default_html_script_types;
+use minify_html_common::esbuild::{CssOptions, JsOptions};
+use minify_html_common::spec::attr::RedundantAttributeCategory;
+use minify_html_common::spec::tag::whitespace::WhitespaceMinification;
+
 /// Configuration settings that can be adjusted and passed to a minification function to change the
 /// minification approach.
+#[derive(Clone)]
 pub struct Cfg {
+    /// Escape all non-ASCII characters so that the output only contains ASCII. Characters in text
+    /// and attribute values are encoded using the shortest named or numeric character reference,
+    /// and characters in JS and CSS are encoded using `\uXXXX` and CSS hex escapes respectively.
+    /// The contents of `<script>` tags that don't contain JavaScript are left as is. C1 control
+    /// characters (U+0080 to U+009F) in text and attribute values are also left as is, as numeric
+    /// character references to them are decoded as Windows-1252 characters instead.
+    pub ascii_only: bool,
+    /// A cache of minified `<script>` and `<style>` contents to use when `minify_js` or
+    /// `minify_css` is enabled, which can be shared across calls so that identical code is only
+    /// minified once.
+    pub cache: Option<Arc<MinifyCache>>,
+    /// Never remove whitespace between or around elements and text, only collapse it to a single
+    /// space or line break. Leading and trailing whitespace is still trimmed in layout elements like
+    /// `<div>` and `<ul>`. This avoids spacing changes when elements aren't displayed as assumed.
+    pub conservative_whitespace: bool,
+    /// Use `display` and `white-space` declarations in `style` attributes, and in rules with type
+    /// or class selectors in the document's `<style>` tags, to avoid removing whitespace that could
+    /// be rendered. For example, whitespace between elements with `display: inline-block` is kept,
+    /// and whitespace in elements with `white-space: pre` is left as is.
+    pub css_aware_whitespace: bool,
+    /// Options passed to esbuild when minifying CSS, if `minify_css` is enabled.
+    pub css_options: CssOptions,
+    /// The URL of the document being minified. If provided, URLs in attributes like `href` and `src`
+    /// are rewritten to their shortest equivalent form (scheme-relative, root-relative, or
+    /// path-relative) relative to this URL or the document's `<base href>` if present.
+    pub document_url: Option<String>,
     /// Ensure all unquoted attribute values in the output do not contain any characters prohibited by the [WHATWG specification](https://html.spec.whatwg.org/multipage/syntax.html#attributes-2).
     pub ensure_spec_compliant_unquoted_attribute_values: bool,
+    /// The maximum number of `<script>` and `<style>` contents that are minified at the same time
+    /// using esbuild, which runs each on its own thread, if `minify_js` or `minify_css` is
+    /// enabled. The rest of the document is minified in the meantime and the results are inserted
+    /// once they're done. Defaults to 0, which uses the number of available CPUs.
+    pub esbuild_threads: usize,
+    /// Types of `<script>` tags whose content is HTML, such as templates used by client-side
+    /// frameworks, which is minified like the rest of the document. Defaults to `text/html`,
+    /// `text/ng-template`, `text/template`, `text/x-handlebars-template`, and `text/x-template`.
+    pub html_script_types: HashSet<Vec<u8>>,
+    /// Options passed to esbuild when minifying JavaScript, if `minify_js` is enabled. This
+    /// includes the code in `<script>` tags and event handler attributes.
+    pub js_options: JsOptions,
     /// Do not omit closing tags when possible.
     pub keep_closing_tags: bool,
     /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes.
     pub keep_html_and_head_opening_tags: bool,
+    /// Categories of attributes that have no effect, such as `type="text/css"` on `<style>`, to
+    /// keep instead of removing. Attributes with their default value or empty values that are
+    /// equivalent to being absent are always removed.
+    pub keep_redundant_attributes: HashSet<RedundantAttributeCategory>,
+    /// Keep comments that are server-side include directives like `<!--#include virtual="..." -->`.
+    /// [Edge Side Includes](https://www.w3.org/TR/esi-lang/) comments like `<!--esi ... -->` are
+    /// always kept.
+    pub keep_server_side_includes: bool,
     /// Keep spaces between attributes when possible to conform to HTML standards.
     pub keep_spaces_between_attributes: bool,
     /// Keep all comments.
     pub keep_comments: bool,
+    /// Keep comments that match any of these rules, such as legal comments like `<!--! ... -->` or
+    /// comments used by frameworks like `<!-- ko -->`. Conditional comments are always kept.
+    pub keep_comments_matching: Vec<CommentRule>,
+    /// When mangling names using [`minify_with_name_map`](crate::minify_with_name_map), also rename
+    /// string literals in `<script>` tags that are exactly a class name or ID, or a selector for one
+    /// like `.name` or `#name`. This could rename strings that aren't used as names, so only enable
+    /// it if scripts are written with this in mind.
+    pub mangle_names_in_scripts: bool,
+    /// Merge adjacent sibling formatting elements like `<b>` and `<span>` that have the same tag
+    /// name and attributes, such as `<b>a</b><b>b</b>`, into a single element. Only `b`, `i`, `em`,
+    /// `strong`, `span`, `small`, `s`, `u`, `sub`, and `sup` elements are merged.
+    pub merge_formatting_elements: bool,
     /// If enabled, CSS in `<style>` tags are minified using
     /// [esbuild-rs](https://github.com/wilsonzlin/esbuild-rs). The `js-esbuild` feature must be
     /// enabled; otherwise, this value has no effect.
@@ -23,24 +93,60 @@ pub struct Cfg {
     /// [MIME type](https://mimesniff.spec.whatwg.org/#javascript-mime-type) is considered to
     /// contain JavaScript, as per the specification.
     pub minify_js: bool,
+    /// Tag names of elements to remove if they have no attributes and would have no content after
+    /// minification, when `remove_empty_elements` is enabled. Defaults to elements that have no
+    /// effect when empty, such as `<p>`, `<div>`, and `<span>`. Elements like `<td>`, `<iframe>`,
+    /// `<textarea>`, and `<script>`, and custom elements, are not included by default, as they can
+    /// have an effect even when empty.
+    pub removable_empty_elements: HashSet<Vec<u8>>,
+    /// Remove elements without attributes that would be empty after whitespace is minified, like
+    /// `<p></p>` and `<div> </div>`, if their tag name is in `removable_empty_elements`.
+    pub remove_empty_elements: bool,
     /// Remove all bangs.
     pub remove_bangs: bool,
     /// Remove all processing_instructions.
     pub remove_processing_instructions: bool,
+    /// Sort the class names in `class` attributes into an order shared across the document, with the
+    /// most frequently used names first, which can improve compression. This doesn't change the
+    /// classes of any element, but does change the order of `Element.classList`. Duplicate class
+    /// names are always removed.
+    pub sort_class_names: bool,
+    /// Override how whitespace is minified in the content of elements with these tag names, which
+    /// must be lowercase. This can be used for custom elements or elements styled with a different
+    /// `display` value. The content of `<pre>` and its descendants is never minified.
+    pub whitespace_minification_overrides: HashMap<Vec<u8>, WhitespaceMinification>,
 }
 
 impl Cfg {
     pub fn new() -> Cfg {
         Cfg {
+            ascii_only: false,
+            cache: None,
+            conservative_whitespace: false,
+            css_aware_whitespace: false,
+            css_options: CssOptions::default(),
+            document_url: None,
             ensure_spec_compliant_unquoted_attribute_values: false,
+            esbuild_threads: 0,
+            html_script_types: default_html_script_types(),
+            js_options: JsOptions::default(),
             keep_closing_tags: false,
             keep_comments: false,
+            keep_comments_matching: Vec::new(),
             keep_html_and_head_opening_tags: false,
+            keep_redundant_attributes: HashSet::new(),
+            keep_server_side_includes: false,
             keep_spaces_between_attributes: false,
+            mangle_names_in_scripts: false,
+            merge_formatting_elements: false,
             minify_css: false,
             minify_js: false,
+            removable_empty_elements: default_removable_empty_elements(),
+            remove_empty_elements: false,
             remove_bangs: false,
             remove_processing_instructions: false,
+            sort_class_names: false,
+            whitespace_minification_overrides: HashMap::new(),
         }
     }
 }
@@ -0,0 +1,38 @@
+use regex::bytes::Regex;
+
+/// A rule for comments to keep when [`Cfg::keep_comments`](crate::Cfg::keep_comments) is
+/// disabled. Rules are matched against the content of a comment between `<!--` and `-->`.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::CommentRule;
+///
+/// // Legal comments like `<!--! Copyright -->`.
+/// let legal = CommentRule::Prefix(b"!".to_vec());
+/// // Knockout containerless bindings like `<!-- ko if: visible -->` and `<!-- /ko -->`.
+/// let knockout = CommentRule::regex(r"^\s*/?ko(\s|$)").unwrap();
+/// // Angular anchors `<!---->`.
+/// let angular = CommentRule::regex("^$").unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub enum CommentRule {
+    /// Keep comments whose content starts with this prefix.
+    Prefix(Vec<u8>),
+    /// Keep comments whose content matches this regular expression.
+    Regex(Regex),
+}
+
+impl CommentRule {
+    /// Creates a rule that keeps comments whose content matches the regular expression `pattern`.
+    pub fn regex(pattern: &str) -> Result<CommentRule, regex::Error> {
+        Regex::new(pattern).map(CommentRule::Regex)
+    }
+
+    pub fn matches(&self, code: &[u8]) -> bool {
+        match self {
+            CommentRule::Prefix(prefix) => code.starts_with(prefix),
+            CommentRule::Regex(regex) => regex.is_match(code),
+        }
+    }
+}
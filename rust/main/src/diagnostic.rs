@@ -0,0 +1,69 @@
+use std::ops::Range;
+
+/// An error reported by esbuild when minifying JS or CSS, usually because the code has a syntax
+/// error.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxError {
+    /// The line in the code where the error is, starting from 1.
+    pub line: usize,
+    /// The column in the line where the error is, in bytes and starting from 0.
+    pub column: usize,
+    pub message: String,
+}
+
+/// Represents the reason some code was left as is instead of being minified.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DiagnosticType {
+    /// The content of a `<script>` tag with a JSON type like `application/ld+json` is not valid
+    /// JSON.
+    MalformedJson,
+    /// The value of a `style` attribute is not a well-formed CSS declaration list.
+    MalformedStyleAttribute,
+    /// esbuild reported errors when minifying the content of a `<script>` tag.
+    ScriptMinificationFailed(Vec<SyntaxError>),
+    /// esbuild reported errors when minifying the value of a `style` attribute, or its output
+    /// did not have the expected structure, in which case there are no errors.
+    StyleAttributeMinificationFailed(Vec<SyntaxError>),
+    /// esbuild reported errors when minifying the content of a `<style>` tag.
+    StyleMinificationFailed(Vec<SyntaxError>),
+}
+
+fn with_first_error(message: &str, errors: &[SyntaxError]) -> String {
+    match errors.first() {
+        Some(e) => format!("{} {} (line {}).", message, e.message, e.line),
+        None => message.to_string(),
+    }
+}
+
+impl DiagnosticType {
+    /// Generates an English message describing the diagnostic.
+    pub fn message(&self) -> String {
+        match self {
+            DiagnosticType::MalformedJson => "Script content is not valid JSON.".to_string(),
+            DiagnosticType::MalformedStyleAttribute => {
+                "Style attribute is not a well-formed CSS declaration list.".to_string()
+            }
+            DiagnosticType::ScriptMinificationFailed(errors) => {
+                with_first_error("Script could not be minified.", errors)
+            }
+            DiagnosticType::StyleAttributeMinificationFailed(errors) => {
+                with_first_error("Style attribute could not be minified.", errors)
+            }
+            DiagnosticType::StyleMinificationFailed(errors) => {
+                with_first_error("Style could not be minified.", errors)
+            }
+        }
+    }
+}
+
+/// Details about a problem encountered during minification. The code that caused it is left
+/// as is, so the output is still equivalent to the source.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub diagnostic_type: DiagnosticType,
+    /// The code that was left as is.
+    pub code: Vec<u8>,
+    /// The position in the source of the element containing the code, from the start of its
+    /// opening tag to the end of its closing tag.
+    pub span: Range<usize>,
+}
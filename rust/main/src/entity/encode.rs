@@ -1,23 +1,91 @@
+use std::collections::HashMap;
+use std::str::from_utf8;
+
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use lazy_static::lazy_static;
 use memchr::memchr;
 
-use minify_html_common::gen::codepoints::ALPHANUMERIC_OR_EQUALS;
+use minify_html_common::ascii::escape_non_ascii;
+use minify_html_common::gen::codepoints::{ALPHANUMERIC_OR_EQUALS, DIGIT, HEX_DIGIT};
 use minify_html_common::gen::entities::{
     EntityType, ENTITY, SHORTER_ENCODED_ENTITIES_DECODED, SHORTER_ENCODED_ENTITIES_ENCODED,
 };
-use minify_html_common::pattern::TrieNodeMatch;
+use minify_html_common::pattern::{TrieNode, TrieNodeMatch};
+
+// Collects the shortest semicolon-terminated named entity for every non-ASCII character that has one.
+// Entities without a semicolon are not considered, as they can unintentionally combine with following characters.
+fn collect_named_entities(
+    node: &TrieNode<EntityType>,
+    name: &mut Vec<u8>,
+    map: &mut HashMap<char, Vec<u8>>,
+) {
+    if let (Some(EntityType::Named(decoded)), Some(b';')) = (node.value, name.last()) {
+        if let Ok(decoded) = from_utf8(decoded) {
+            let mut chars = decoded.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                if !c.is_ascii() && map.get(&c).filter(|n| n.len() <= name.len()).is_none() {
+                    map.insert(c, name.clone());
+                };
+            };
+        };
+    };
+    for (i, child) in node.children.iter().enumerate() {
+        if let Some(child) = child {
+            name.push((node.offset + i) as u8);
+            collect_named_entities(child, name, map);
+            name.pop();
+        };
+    }
+}
 
 lazy_static! {
     static ref SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER: AhoCorasick = AhoCorasickBuilder::new()
         .dfa(true)
         .match_kind(MatchKind::LeftmostLongest)
         .build(SHORTER_ENCODED_ENTITIES_DECODED);
+    static ref NAMED_ENTITY_FOR_CHAR: HashMap<char, Vec<u8>> = {
+        let mut map = HashMap::new();
+        collect_named_entities(ENTITY, &mut Vec::new(), &mut map);
+        map
+    };
+}
+
+// Writes the shortest character reference for `c`.
+// Numeric references only get a semicolon if the next character would otherwise be consumed as part of it.
+// C1 control characters (U+0080 to U+009F) are written as is, as decimal and hexadecimal references to them are decoded
+// as their Windows-1252 characters instead, e.g. `&#128` is `€`.
+// https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-end-state
+fn write_shortest_char_ref(out: &mut Vec<u8>, c: char, next: Option<u8>) {
+    if ('\u{80}'..='\u{9F}').contains(&c) {
+        let mut buf = [0; 4];
+        out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        return;
+    };
+    let mut dec = format!("&#{}", c as u32).into_bytes();
+    if next.filter(|&n| DIGIT[n] || n == b';').is_some() {
+        dec.push(b';');
+    };
+    let mut hex = format!("&#x{:x}", c as u32).into_bytes();
+    if next.filter(|&n| HEX_DIGIT[n] || n == b';').is_some() {
+        hex.push(b';');
+    };
+    // When lengths are equal, prefer decimal to all and hexadecimal to named.
+    let mut min = &dec;
+    if hex.len() < min.len() {
+        min = &hex;
+    };
+    if let Some(named) = NAMED_ENTITY_FOR_CHAR.get(&c) {
+        if named.len() < min.len() {
+            min = named;
+        };
+    };
+    out.extend_from_slice(min);
 }
 
 // Encodes ampersands when necessary, as well as UTF-8 sequences that are shorter encoded.
+// If `ascii_only`, all remaining non-ASCII characters are also encoded using their shortest character reference.
 // Does not handle context-specific escaping e.g. `>`, `'`, `"`.
-pub fn encode_entities(mut code: &[u8], in_attr_val: bool) -> Vec<u8> {
+pub fn encode_entities(mut code: &[u8], in_attr_val: bool, ascii_only: bool) -> Vec<u8> {
     let mut res = Vec::<u8>::new();
     while !code.is_empty() {
         let (before, matched) = match memchr(b'&', code) {
@@ -57,6 +125,11 @@ pub fn encode_entities(mut code: &[u8], in_attr_val: bool) -> Vec<u8> {
             code = &code[end..];
         };
     }
-    SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER
-        .replace_all_bytes(&res, SHORTER_ENCODED_ENTITIES_ENCODED)
+    let res = SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER
+        .replace_all_bytes(&res, SHORTER_ENCODED_ENTITIES_ENCODED);
+    if ascii_only {
+        escape_non_ascii(&res, write_shortest_char_ref)
+    } else {
+        res
+    }
 }
@@ -2,7 +2,11 @@ use crate::entity::encode::encode_entities;
 
 #[test]
 fn test_encode_entities_encodes_ampersands_when_they_form_valid_entities() {
-    let out = encode_entities(b"1 is < &than 2 Y&amp;&ClockwiseContourIntegral", false);
+    let out = encode_entities(
+        b"1 is < &than 2 Y&amp;&ClockwiseContourIntegral",
+        false,
+        false,
+    );
     assert_eq!(
         std::str::from_utf8(&out).unwrap(),
         "1 is < &than 2 Y&ampamp;&ClockwiseContourIntegral"
@@ -12,7 +16,11 @@ fn test_encode_entities_encodes_ampersands_when_they_form_valid_entities() {
 #[test]
 fn test_encode_entities_does_not_encode_valid_named_entities_inside_an_attr_value_if_they_do_not_end_with_a_semicolon_but_are_followed_by_an_alphanumeric_or_equals_character(
 ) {
-    let out = encode_entities(b"https://a.com/b?c  = d&param=123&param;&lt&mdash;", true);
+    let out = encode_entities(
+        b"https://a.com/b?c  = d&param=123&param;&lt&mdash;",
+        true,
+        false,
+    );
     assert_eq!(
         std::str::from_utf8(&out).unwrap(),
         "https://a.com/b?c  = d&param=123&param;&amplt&ampmdash;"
@@ -21,6 +29,38 @@ fn test_encode_entities_does_not_encode_valid_named_entities_inside_an_attr_valu
 
 #[test]
 fn test_encode_entities_encodes_utf8_sequences_that_are_shorter_encoded() {
-    let out = encode_entities("\u{226A}\u{20D2}".as_bytes(), false);
+    let out = encode_entities("\u{226A}\u{20D2}".as_bytes(), false, false);
     assert_eq!(std::str::from_utf8(&out).unwrap(), "&nLt;");
 }
+
+#[test]
+fn test_encode_entities_encodes_non_ascii_characters_using_shortest_reference_if_ascii_only() {
+    let out = encode_entities(
+        "caf\u{E9} \u{3C0}\u{2260}3 \u{A9}2021 \u{1F600}".as_bytes(),
+        false,
+        true,
+    );
+    assert_eq!(
+        std::str::from_utf8(&out).unwrap(),
+        "caf&#233 &pi;&ne;3 &#169;2021 &#128512"
+    );
+    let out = encode_entities(
+        "\u{E9}f \u{E9}; \u{FFFFF} \u{226A}\u{20D2}".as_bytes(),
+        true,
+        true,
+    );
+    assert_eq!(
+        std::str::from_utf8(&out).unwrap(),
+        "&#233f &#233;; &#xfffff &nLt;"
+    );
+}
+
+#[test]
+fn test_encode_entities_does_not_encode_c1_control_characters_if_ascii_only() {
+    // `&#128` and `&#x80` would be decoded as `\u{20AC}`.
+    let out = encode_entities("\u{7F}\u{80}\u{9F}\u{A0}".as_bytes(), false, true);
+    assert_eq!(
+        std::str::from_utf8(&out).unwrap(),
+        "\u{7F}\u{80}\u{9F}&#160"
+    );
+}
@@ -1,15 +1,37 @@
+pub use crate::cache::MinifyCache;
 pub use crate::cfg::Cfg;
+pub use crate::comment_rule::CommentRule;
+pub use crate::diagnostic::{Diagnostic, DiagnosticType, SyntaxError};
+use crate::minify::class::get_class_order;
 use crate::minify::content::minify_content;
+use crate::minify::context::MinifyContext;
+#[cfg(feature = "js-esbuild")]
+use crate::minify::esbuild::EsbuildQueue;
+use crate::minify::mangle::add_document_names;
+use crate::minify::url::get_base_url;
+use crate::minify::whitespace_hints::get_stylesheet_hints;
+pub use crate::name_map::NameMap;
 use crate::parse::content::parse_content;
 use crate::parse::Code;
+#[cfg(feature = "js-esbuild")]
+use minify_html_common::esbuild::EsbuildTransformOptions;
+pub use minify_html_common::esbuild::{CssOptions, EsTarget, JsOptions};
+pub use minify_html_common::spec::attr::RedundantAttributeCategory;
 use minify_html_common::spec::tag::ns::Namespace;
+pub use minify_html_common::spec::tag::whitespace::WhitespaceMinification;
 use minify_html_common::spec::tag::EMPTY_SLICE;
 
 mod ast;
+mod cache;
 mod cfg;
+mod comment_rule;
+mod diagnostic;
 mod entity;
 mod minify;
+mod name_map;
 mod parse;
+#[cfg(test)]
+mod tests;
 
 /// Minifies UTF-8 HTML code, represented as an array of bytes.
 ///
@@ -30,9 +52,107 @@ mod parse;
 /// assert_eq!(minified, b"<p>Hello, world!".to_vec());
 /// ```
 pub fn minify(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+    minify_with_diagnostics(src, cfg).0
+}
+
+/// Minifies UTF-8 HTML code like [`minify`], and also returns details about any code that was
+/// left as is because it could not be minified.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_with_diagnostics};
+///
+/// let mut code: &[u8] = b"<p style=\"color: red\">  Hello, world!  </p>";
+/// let mut cfg = Cfg::new();
+/// cfg.minify_css = true;
+/// let (minified, diagnostics) = minify_with_diagnostics(&code, &cfg);
+/// for d in diagnostics {
+///     eprintln!("{}", d.diagnostic_type.message());
+/// }
+/// ```
+pub fn minify_with_diagnostics(src: &[u8], cfg: &Cfg) -> (Vec<u8>, Vec<Diagnostic>) {
+    let (out, ctx) = minify_document(src, cfg, None);
+    (out, ctx.diagnostics)
+}
+
+/// Minifies UTF-8 HTML code like [`minify_with_diagnostics`], and also renames class names and IDs
+/// to short generated names. Names already in `name_map` are renamed as specified, and names used
+/// in the document that aren't are added to it, so it can be saved and used to apply the same
+/// renaming to other documents or to separate CSS and JS code.
+///
+/// Names are renamed in `class` and `id` attributes, attributes that reference IDs like `for` and
+/// `aria-labelledby`, `href` values like `#id`, selectors in `<style>` tags, and `url(#id)`
+/// references in `<style>` tags, `style` attributes, and SVG attributes like `fill`. If
+/// [`Cfg::mangle_names_in_scripts`] is enabled, string literals in `<script>` tags that are exactly
+/// a name or a selector for one like `.name` are renamed too.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_with_name_map, NameMap};
+///
+/// let mut code: &[u8] = b"<p class=\"message\">Hello, world!</p>";
+/// let cfg = Cfg::new();
+/// let mut name_map = NameMap::new();
+/// let (minified, _) = minify_with_name_map(&code, &cfg, &mut name_map);
+/// assert_eq!(minified, b"<p class=a>Hello, world!".to_vec());
+/// assert_eq!(name_map.classes["message"], "a");
+/// ```
+pub fn minify_with_name_map(
+    src: &[u8],
+    cfg: &Cfg,
+    name_map: &mut NameMap,
+) -> (Vec<u8>, Vec<Diagnostic>) {
+    let (out, ctx) = minify_document(src, cfg, Some(std::mem::take(name_map)));
+    *name_map = ctx.name_map.unwrap();
+    (out, ctx.diagnostics)
+}
+
+fn minify_document(
+    src: &[u8],
+    cfg: &Cfg,
+    mut name_map: Option<NameMap>,
+) -> (Vec<u8>, MinifyContext) {
     let mut code = Code::new(src);
     let parsed = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE);
+    if let Some(name_map) = name_map.as_mut() {
+        add_document_names(name_map, &parsed.children);
+    };
+    let mut ctx = MinifyContext {
+        base_url: get_base_url(cfg, &parsed.children),
+        class_order: if cfg.sort_class_names {
+            Some(get_class_order(&parsed.children))
+        } else {
+            None
+        },
+        diagnostics: Vec::new(),
+        #[cfg(feature = "js-esbuild")]
+        esbuild_queue: EsbuildQueue::new(cfg),
+        #[cfg(feature = "js-esbuild")]
+        esbuild_options: EsbuildTransformOptions::new(
+            &cfg.js_options,
+            &cfg.css_options,
+            cfg.ascii_only,
+        ),
+        name_map,
+        stylesheet_hints: if cfg.css_aware_whitespace {
+            Some(get_stylesheet_hints(&parsed.children))
+        } else {
+            None
+        },
+    };
     let mut out = Vec::with_capacity(src.len());
-    minify_content(cfg, &mut out, false, EMPTY_SLICE, parsed.children);
-    out
+    minify_content(
+        cfg,
+        &mut ctx,
+        &mut out,
+        false,
+        EMPTY_SLICE,
+        false,
+        parsed.children,
+    );
+    #[cfg(feature = "js-esbuild")]
+    let out = ctx.esbuild_queue.finish(cfg, out, &mut ctx.diagnostics);
+    (out, ctx)
 }
@@ -1,17 +1,29 @@
 use aho_corasick::{AhoCorasickBuilder, MatchKind};
 use lazy_static::lazy_static;
+use std::ops::Range;
 
 #[cfg(feature = "js-esbuild")]
 use {
-    crate::minify::css::MINIFY_CSS_TRANSFORM_OPTIONS, crate::minify::esbuild::minify_using_esbuild,
+    crate::cache::{cached, MinifyCache},
+    crate::diagnostic::Diagnostic,
+    crate::minify::css::minify_css_declarations,
+    crate::minify::js::{minify_js_function_body, minify_js_url_script},
 };
 
 use crate::entity::encode::encode_entities;
+use crate::minify::class::minify_class;
+use crate::minify::context::MinifyContext;
+use crate::minify::mangle::{
+    is_id_ref_attr, is_url_ref_presentation_attr, mangle_css_declarations, mangle_id_refs,
+    mangled_id,
+};
+use crate::minify::sizes::minify_sizes;
+use crate::minify::srcset::minify_srcset;
+use crate::minify::url::minify_url;
 use crate::Cfg;
 use minify_html_common::gen::attrs::ATTRS;
 use minify_html_common::gen::codepoints::DIGIT;
 use minify_html_common::pattern::Replacer;
-use minify_html_common::spec::script::JAVASCRIPT_MIME_TYPES;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::whitespace::{collapse_whitespace, left_trim, right_trim};
 
@@ -272,18 +284,39 @@ pub fn encode_unquoted(val: &[u8], whatwg: bool) -> AttrMinifiedValue {
     }
 }
 
+// Event handler attributes don't have a fixed list, so use the same heuristic as most tools.
+#[cfg(feature = "js-esbuild")]
+fn is_event_handler_attr(name: &[u8]) -> bool {
+    name.len() > 4 && name.starts_with(b"on") && name[2..].iter().all(|c| c.is_ascii_lowercase())
+}
+
+#[cfg(feature = "js-esbuild")]
+const JS_URL_SCHEME: &[u8] = b"javascript:";
+
+#[cfg(feature = "js-esbuild")]
+fn is_js_url(value: &[u8]) -> bool {
+    value
+        .get(..JS_URL_SCHEME.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(JS_URL_SCHEME))
+        .is_some()
+}
+
 pub enum AttrMinified {
     Redundant,
     NoValue,
     Value(AttrMinifiedValue),
 }
 
+// `span` is the position of the element in the source, used for diagnostics.
+#[cfg_attr(not(feature = "js-esbuild"), allow(unused_variables))]
 pub fn minify_attr(
     cfg: &Cfg,
+    ctx: &mut MinifyContext,
     ns: Namespace,
     tag: &[u8],
     name: &[u8],
     mut value_raw: Vec<u8>,
+    span: &Range<usize>,
 ) -> AttrMinified {
     let attr_cfg = ATTRS.get(ns, tag, name);
 
@@ -292,6 +325,7 @@ pub fn minify_attr(
     // An attribute can have both redundant_if_empty and default_value, which means it has two default values: "" and default_value.
     let redundant_if_empty = attr_cfg.filter(|attr| attr.redundant_if_empty).is_some();
     let default_value = attr_cfg.and_then(|attr| attr.default_value);
+    let is_url = attr_cfg.filter(|attr| attr.url).is_some();
 
     // Trim before checking is_boolean as the entire attribute could be redundant post-minification.
     if should_collapse_and_trim {
@@ -300,33 +334,97 @@ pub fn minify_attr(
         collapse_whitespace(&mut value_raw);
     };
 
+    if let (true, Some(base_url)) = (is_url, ctx.base_url.as_ref()) {
+        value_raw = minify_url(base_url, &value_raw);
+    };
+
+    if name == b"class" {
+        value_raw = minify_class(ctx.class_order.as_ref(), ctx.name_map.as_ref(), &value_raw);
+        if value_raw.is_empty() {
+            return AttrMinified::Redundant;
+        };
+    };
+
+    if let Some(name_map) = &ctx.name_map {
+        if name == b"id" {
+            value_raw = mangled_id(name_map, &value_raw).to_vec();
+        } else if ns == Namespace::Html && is_id_ref_attr(name) {
+            value_raw = mangle_id_refs(name_map, &value_raw);
+        } else if (name == b"href" || name == b"xlink:href") && value_raw.starts_with(b"#") {
+            let id = mangled_id(name_map, &value_raw[1..]).to_vec();
+            value_raw.truncate(1);
+            value_raw.extend_from_slice(&id);
+        } else if name == b"style" || (ns == Namespace::Svg && is_url_ref_presentation_attr(name)) {
+            value_raw = mangle_css_declarations(name_map, &value_raw);
+        };
+    };
+
+    if ns == Namespace::Html {
+        let min = match (tag, name) {
+            (b"img" | b"source", b"srcset") | (b"link", b"imagesrcset") => {
+                minify_srcset(ctx.base_url.as_ref(), &value_raw)
+            }
+            (b"img" | b"source", b"sizes") | (b"link", b"imagesizes") => minify_sizes(&value_raw),
+            _ => None,
+        };
+        if let Some(min) = min {
+            value_raw = min;
+        };
+    };
+
     #[cfg(feature = "js-esbuild")]
-    if name == b"style" && cfg.minify_css {
-        let mut value_raw_wrapped = Vec::with_capacity(value_raw.len() + 3);
-        // TODO This isn't safe for invalid input e.g. `a}/*`.
-        value_raw_wrapped.extend_from_slice(b"x{");
-        value_raw_wrapped.extend_from_slice(&value_raw);
-        value_raw_wrapped.push(b'}');
-        let mut value_raw_wrapped_min = Vec::with_capacity(value_raw_wrapped.len());
-        minify_using_esbuild(
-            &mut value_raw_wrapped_min,
-            &value_raw_wrapped,
-            &MINIFY_CSS_TRANSFORM_OPTIONS.clone(),
-        );
-        // If input was invalid, wrapper syntax may not exist anymore.
-        if value_raw_wrapped_min.starts_with(b"x{") {
-            value_raw_wrapped_min.drain(0..2);
+    if cfg.minify_js && is_event_handler_attr(name) {
+        if let Ok(min) = cached(
+            cfg.cache.as_deref(),
+            || {
+                let options = (&cfg.js_options, cfg.ascii_only);
+                MinifyCache::key("js-function-body", &options, &value_raw)
+            },
+            || minify_js_function_body(&ctx.esbuild_options.js, &value_raw).ok_or(()),
+        ) {
+            value_raw = min;
         };
-        if value_raw_wrapped_min.ends_with(b"}") {
-            value_raw_wrapped_min.pop();
+    };
+
+    // `javascript:` URLs are run as scripts, not function bodies, as their completion value can replace the document.
+    #[cfg(feature = "js-esbuild")]
+    if cfg.minify_js && is_url && is_js_url(&value_raw) {
+        let (scheme, code) = value_raw.split_at(JS_URL_SCHEME.len());
+        if let Ok(min) = cached(
+            cfg.cache.as_deref(),
+            || {
+                let options = (&cfg.js_options, cfg.ascii_only);
+                MinifyCache::key("js-url", &options, code)
+            },
+            || minify_js_url_script(&ctx.esbuild_options.js_url, code).ok_or(()),
+        ) {
+            let mut url = scheme.to_vec();
+            url.extend_from_slice(&min);
+            value_raw = url;
         };
-        value_raw = value_raw_wrapped_min;
-    }
+    };
+
+    #[cfg(feature = "js-esbuild")]
+    if name == b"style" && cfg.minify_css {
+        match cached(
+            cfg.cache.as_deref(),
+            || {
+                let options = (&cfg.css_options, cfg.ascii_only);
+                MinifyCache::key("css-declarations", &options, &value_raw)
+            },
+            || minify_css_declarations(&ctx.esbuild_options.css, &value_raw),
+        ) {
+            Ok(min) => value_raw = min,
+            Err(diagnostic_type) => ctx.diagnostics.push(Diagnostic {
+                diagnostic_type,
+                code: value_raw.clone(),
+                span: span.clone(),
+            }),
+        };
+    };
 
     if (value_raw.is_empty() && redundant_if_empty)
         || default_value.filter(|dv| dv == &value_raw).is_some()
-        // TODO Cfg.
-        || (tag == b"script" && JAVASCRIPT_MIME_TYPES.contains(value_raw.as_slice()))
     {
         return AttrMinified::Redundant;
     };
@@ -335,7 +433,7 @@ pub fn minify_attr(
         return AttrMinified::NoValue;
     };
 
-    let encoded = encode_entities(&value_raw, true);
+    let encoded = encode_entities(&value_raw, true, cfg.ascii_only);
 
     // When lengths are equal, prefer double quotes to all and single quotes to unquoted.
     let mut min = encode_using_double_quotes(&encoded);
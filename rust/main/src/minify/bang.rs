@@ -1,7 +1,9 @@
 use crate::cfg::Cfg;
+use minify_html_common::spec::comment::is_conditional_bang;
 
 pub fn minify_bang(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8], ended: bool) {
-    if !cfg.remove_bangs {
+    // Conditional comments in the `<![if !IE]>` form are always kept, as removing them changes what's rendered.
+    if !cfg.remove_bangs || (ended && is_conditional_bang(code)) {
         out.extend_from_slice(b"<!");
         out.extend_from_slice(code);
         if ended {
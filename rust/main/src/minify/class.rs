@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::ast::NodeData;
+use crate::minify::mangle::mangled_class;
+use crate::name_map::NameMap;
+use minify_html_common::gen::codepoints::WHITESPACE;
+
+fn class_names(value: &[u8]) -> impl Iterator<Item = &[u8]> {
+    value.split(|&c| WHITESPACE[c]).filter(|c| !c.is_empty())
+}
+
+fn count_class_names<'a>(counts: &mut HashMap<&'a [u8], usize>, nodes: &'a [NodeData]) {
+    for n in nodes {
+        if let NodeData::Element {
+            attributes,
+            children,
+            ..
+        } = n
+        {
+            if let Some(class) = attributes.get(b"class".as_ref()) {
+                for c in class_names(class) {
+                    *counts.entry(c).or_default() += 1;
+                }
+            };
+            count_class_names(counts, children);
+        };
+    }
+}
+
+// Returns the position of every class name in an order shared by all `class` attributes in the document.
+// More frequent names come first, so that `class` values have more common prefixes and compress better.
+pub fn get_class_order(nodes: &[NodeData]) -> HashMap<Vec<u8>, usize> {
+    let mut counts = HashMap::new();
+    count_class_names(&mut counts, nodes);
+    let mut names = counts.into_iter().collect::<Vec<_>>();
+    names.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, _))| (name.to_vec(), i))
+        .collect()
+}
+
+// Removes duplicate class names and collapses the whitespace between them. If `order` is provided, names are sorted
+// using it, which doesn't change the set of classes the element has. If `name_map` is provided, names are then mangled.
+pub fn minify_class(
+    order: Option<&HashMap<Vec<u8>, usize>>,
+    name_map: Option<&NameMap>,
+    value: &[u8],
+) -> Vec<u8> {
+    let mut names = Vec::<&[u8]>::new();
+    for c in class_names(value) {
+        if !names.contains(&c) {
+            names.push(c);
+        };
+    }
+    if let Some(order) = order {
+        names.sort_by_key(|c| order.get(*c).copied().unwrap_or(usize::MAX));
+    };
+    if let Some(name_map) = name_map {
+        for c in names.iter_mut() {
+            *c = mangled_class(name_map, c);
+        }
+    };
+    names.join(b" ".as_ref())
+}
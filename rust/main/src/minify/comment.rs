@@ -1,11 +1,83 @@
 use crate::cfg::Cfg;
+use crate::minify::context::MinifyContext;
+use crate::minify::fragment::minify_html_fragment;
+use minify_html_common::spec::comment::{
+    get_conditional_comment, is_esi_comment, is_server_side_include, ConditionalComment,
+};
 
-pub fn minify_comment(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8], ended: bool) {
-    if cfg.keep_comments {
-        out.extend_from_slice(b"<!--");
-        out.extend_from_slice(code);
-        if ended {
+// Whether a comment is kept. Conditional comments and ESI comments are always kept.
+pub fn is_kept_comment(cfg: &Cfg, code: &[u8]) -> bool {
+    get_conditional_comment(code).is_some()
+        || is_esi_comment(code)
+        || cfg.keep_comments
+        || (cfg.keep_server_side_includes && is_server_side_include(code))
+        || cfg.keep_comments_matching.iter().any(|r| r.matches(code))
+}
+
+// Whether a comment contains HTML that's used in some situations, so it should be treated like an element when
+// deciding whether the closing tag of the element before it can be omitted.
+pub fn comment_contains_html(code: &[u8]) -> bool {
+    is_esi_comment(code)
+        || matches!(
+            get_conditional_comment(code),
+            Some(ConditionalComment::DownlevelHidden { .. })
+        )
+}
+
+// `code_start` is the position of the comment's content in the source.
+pub fn minify_comment(
+    cfg: &Cfg,
+    ctx: &mut MinifyContext,
+    out: &mut Vec<u8>,
+    descendant_of_pre: bool,
+    code: &[u8],
+    code_start: usize,
+    ended: bool,
+) {
+    if ended && is_esi_comment(code) {
+        // ESI processors remove the `<!--esi` and `-->`, so the whitespace after `esi` is needed but the rest isn't.
+        out.extend_from_slice(b"<!--esi ");
+        minify_html_fragment(
+            cfg,
+            ctx,
+            out,
+            descendant_of_pre,
+            &code[4..],
+            code_start + 4,
+            b"-->",
+        );
+        out.extend_from_slice(b"-->");
+        return;
+    };
+    match get_conditional_comment(code).filter(|_| ended) {
+        Some(ConditionalComment::DownlevelHidden { opening_len }) => {
+            let closing_start = code.len() - b"<![endif]".len();
+            out.extend_from_slice(b"<!--");
+            out.extend_from_slice(&code[..opening_len]);
+            minify_html_fragment(
+                cfg,
+                ctx,
+                out,
+                descendant_of_pre,
+                &code[opening_len..closing_start],
+                code_start + opening_len,
+                b"-->",
+            );
+            out.extend_from_slice(&code[closing_start..]);
+            out.extend_from_slice(b"-->");
+        }
+        Some(_) => {
+            out.extend_from_slice(b"<!--");
+            out.extend_from_slice(code);
             out.extend_from_slice(b"-->");
-        };
+        }
+        None if is_kept_comment(cfg, code) => {
+            out.extend_from_slice(b"<!--");
+            out.extend_from_slice(code);
+            if ended {
+                out.extend_from_slice(b"-->");
+            };
+        }
+        None => {}
     };
 }
@@ -1,22 +1,37 @@
 use aho_corasick::{AhoCorasickBuilder, MatchKind};
 use lazy_static::lazy_static;
+use std::ops::Range;
+#[cfg(feature = "js-esbuild")]
+use {
+    crate::cache::MinifyCache,
+    crate::minify::esbuild::{EsbuildJob, EsbuildLang},
+    std::sync::Arc,
+};
 
 use crate::ast::{NodeData, ScriptOrStyleLang};
 use crate::cfg::Cfg;
+use crate::diagnostic::{Diagnostic, DiagnosticType};
 use crate::entity::encode::encode_entities;
 use crate::minify::bang::minify_bang;
-use crate::minify::comment::minify_comment;
-use crate::minify::css::minify_css;
-use crate::minify::element::minify_element;
+use crate::minify::comment::{comment_contains_html, minify_comment};
+use crate::minify::context::MinifyContext;
+use crate::minify::css::write_css;
+use crate::minify::element::{minify_element, ElementPosition};
+use crate::minify::empty::remove_empty_elements;
 use crate::minify::instruction::minify_instruction;
-use crate::minify::js::minify_js;
+use crate::minify::js::write_js;
+use crate::minify::json::minify_json;
+use crate::minify::mangle::{mangle_css, mangle_js_strings};
+use crate::minify::merge::merge_formatting_elements;
+use crate::minify::whitespace_hints::get_element_hint;
 use minify_html_common::gen::codepoints::TAG_NAME_CHAR;
 use minify_html_common::pattern::Replacer;
 use minify_html_common::spec::tag::whitespace::{
     get_whitespace_minification_for_tag, WhitespaceMinification,
 };
 use minify_html_common::whitespace::{
-    collapse_whitespace, is_all_whitespace, left_trim, right_trim,
+    collapse_whitespace, collapse_whitespace_keeping_line_breaks, is_all_whitespace, left_trim,
+    right_trim,
 };
 
 fn build_chevron_replacer() -> Replacer {
@@ -45,19 +60,75 @@ lazy_static! {
     static ref CHEVRON_REPLACER: Replacer = build_chevron_replacer();
 }
 
+// Returns how whitespace in the content of `parent` is minified, after applying any options that affect it.
+pub fn get_whitespace_minification(
+    cfg: &Cfg,
+    ctx: &MinifyContext,
+    descendant_of_pre: bool,
+    // Use empty slice if none.
+    parent: &[u8],
+    parent_is_inline: bool,
+    nodes: &[NodeData],
+) -> WhitespaceMinification {
+    let mut min = *get_whitespace_minification_for_tag(
+        &cfg.whitespace_minification_overrides,
+        parent,
+        descendant_of_pre,
+    );
+    if cfg.conservative_whitespace {
+        // Only layout elements destroy whole whitespace, and they are the only ones we still trim in.
+        min.trim &= min.destroy_whole;
+        min.destroy_whole = false;
+    };
+    if parent_is_inline {
+        min.destroy_whole = false;
+        min.trim = false;
+    };
+    if let Some(hints) = &ctx.stylesheet_hints {
+        // Whitespace between inline-level elements is rendered.
+        if min.destroy_whole
+            && nodes.iter().any(|n| match n {
+                NodeData::Element {
+                    attributes, name, ..
+                } => get_element_hint(hints, name, attributes).inline,
+                _ => false,
+            })
+        {
+            min.destroy_whole = false;
+        };
+    };
+    min
+}
+
 pub fn minify_content(
     cfg: &Cfg,
+    ctx: &mut MinifyContext,
     out: &mut Vec<u8>,
     descendant_of_pre: bool,
     // Use empty slice if none.
     parent: &[u8],
+    // Whether CSS-aware whitespace minification found that the parent has an inline-level `display`.
+    parent_is_inline: bool,
     mut nodes: Vec<NodeData>,
 ) {
-    let &WhitespaceMinification {
+    if cfg.remove_empty_elements {
+        remove_empty_elements(cfg, ctx, descendant_of_pre, &mut nodes);
+    };
+    if cfg.merge_formatting_elements {
+        merge_formatting_elements(&mut nodes);
+    };
+    let WhitespaceMinification {
         collapse,
         destroy_whole,
         trim,
-    } = get_whitespace_minification_for_tag(parent, descendant_of_pre);
+    } = get_whitespace_minification(
+        cfg,
+        ctx,
+        descendant_of_pre,
+        parent,
+        parent_is_inline,
+        &nodes,
+    );
 
     // TODO Document or fix: even though bangs/comments/etc. don't affect layout, we don't collapse/destroy-whole/trim combined text nodes across bangs/comments/etc., as that's too complex and is ambiguous about which nodes should whitespace be deleted from.
     let mut found_first_text_or_elem = false;
@@ -82,6 +153,10 @@ pub fn minify_content(
                 index_of_last_nonempty_text_or_elem = i as isize;
                 index_of_last_text_or_elem = i as isize;
             }
+            NodeData::Comment { code, .. } if comment_contains_html(code) => {
+                // The previous element isn't the last node, and its closing tag can't be omitted based on the next element.
+                index_of_last_nonempty_text_or_elem = i as isize;
+            }
             NodeData::Text { value } => {
                 if !found_first_text_or_elem {
                     // This is the first element or text node, and it's a text node.
@@ -95,6 +170,8 @@ pub fn minify_content(
                 // TODO debug_assert this and add tests.
                 if destroy_whole && is_all_whitespace(value) {
                     value.clear();
+                } else if collapse && cfg.conservative_whitespace {
+                    collapse_whitespace_keeping_line_breaks(value);
                 } else if collapse {
                     collapse_whitespace(value);
                 };
@@ -115,10 +192,21 @@ pub fn minify_content(
         };
     }
 
+    // The tag name of the previous element if it was the last node written and its closing tag was omitted.
+    let mut previous_sibling_with_omitted_closing_tag = Vec::new();
     for (i, c) in nodes.into_iter().enumerate() {
+        let out_len = out.len();
         match c {
             NodeData::Bang { code, ended } => minify_bang(cfg, out, &code, ended),
-            NodeData::Comment { code, ended } => minify_comment(cfg, out, &code, ended),
+            NodeData::Comment { code, ended, span } => minify_comment(
+                cfg,
+                ctx,
+                out,
+                descendant_of_pre,
+                &code,
+                span.start + 4,
+                ended,
+            ),
             NodeData::Element {
                 attributes,
                 children,
@@ -126,27 +214,132 @@ pub fn minify_content(
                 name,
                 namespace: child_ns,
                 next_sibling_element_name,
-            } => minify_element(
-                cfg,
-                out,
-                descendant_of_pre,
-                child_ns,
-                parent,
-                &next_sibling_element_name,
-                (i as isize) == index_of_last_nonempty_text_or_elem,
-                &name,
-                attributes,
-                closing_tag,
-                children,
-            ),
+                span,
+            } => {
+                let closing_tag_omitted = minify_element(
+                    cfg,
+                    ctx,
+                    out,
+                    descendant_of_pre,
+                    child_ns,
+                    ElementPosition {
+                        parent,
+                        next_sibling_as_element_tag_name: &next_sibling_element_name,
+                        is_last_child_text_or_element_node: (i as isize)
+                            == index_of_last_nonempty_text_or_elem,
+                        previous_sibling_with_omitted_closing_tag:
+                            &previous_sibling_with_omitted_closing_tag,
+                    },
+                    &name,
+                    attributes,
+                    closing_tag,
+                    children,
+                    span,
+                );
+                previous_sibling_with_omitted_closing_tag = if closing_tag_omitted {
+                    name
+                } else {
+                    Vec::new()
+                };
+                continue;
+            }
             NodeData::Instruction { code, ended } => minify_instruction(cfg, out, &code, ended),
-            NodeData::ScriptOrStyleContent { code, lang } => match lang {
-                ScriptOrStyleLang::CSS => minify_css(cfg, out, &code),
-                ScriptOrStyleLang::Data => out.extend_from_slice(&code),
-                ScriptOrStyleLang::JS => minify_js(cfg, out, &code),
-            },
-            NodeData::Text { value } => out
-                .extend_from_slice(&CHEVRON_REPLACER.replace_all(&encode_entities(&value, false))),
+            // This is handled by `minify_element`, as it's always the only child of a `<script>` or `<style>`.
+            NodeData::ScriptOrStyleContent { .. } => unreachable!(),
+            NodeData::Text { value } => out.extend_from_slice(
+                &CHEVRON_REPLACER.replace_all(&encode_entities(&value, false, cfg.ascii_only)),
+            ),
+        };
+        if out.len() != out_len {
+            previous_sibling_with_omitted_closing_tag.clear();
         };
     }
 }
+
+// Minifies the content of a `<script>` or `<style>` tag. `span` is the position of the element in the source, used for
+// diagnostics. If esbuild is used, the minified code is inserted into the output once the whole document is minified.
+pub fn minify_script_or_style_content(
+    cfg: &Cfg,
+    ctx: &mut MinifyContext,
+    out: &mut Vec<u8>,
+    code_in_source: &[u8],
+    lang: ScriptOrStyleLang,
+    span: Range<usize>,
+) {
+    let code = code_in_source;
+    let diagnostic_type = match lang {
+        ScriptOrStyleLang::CSS => {
+            let code = match &ctx.name_map {
+                Some(name_map) => mangle_css(name_map, code),
+                None => code.to_vec(),
+            };
+            #[cfg(feature = "js-esbuild")]
+            if cfg.minify_css {
+                ctx.esbuild_queue.push(
+                    out,
+                    EsbuildJob {
+                        lang: EsbuildLang::Css,
+                        cache_key: cfg.cache.as_ref().map(|_| {
+                            MinifyCache::key("css", &(&cfg.css_options, cfg.ascii_only), &code)
+                        }),
+                        code: Arc::new(code),
+                        transform_options: ctx.esbuild_options.css.clone(),
+                        source_code: code_in_source.to_vec(),
+                        span,
+                    },
+                );
+                return;
+            };
+            write_css(cfg, out, &code);
+            None
+        }
+        ScriptOrStyleLang::Data => {
+            out.extend_from_slice(code);
+            None
+        }
+        ScriptOrStyleLang::Json => match minify_json(code) {
+            Some(min) => {
+                out.extend_from_slice(&min);
+                None
+            }
+            None => {
+                out.extend_from_slice(code);
+                Some(DiagnosticType::MalformedJson)
+            }
+        },
+        ScriptOrStyleLang::JS | ScriptOrStyleLang::JSModule => {
+            let code = match &ctx.name_map {
+                Some(name_map) if cfg.mangle_names_in_scripts => mangle_js_strings(name_map, code),
+                _ => code.to_vec(),
+            };
+            #[cfg(feature = "js-esbuild")]
+            if cfg.minify_js {
+                let module = lang == ScriptOrStyleLang::JSModule;
+                ctx.esbuild_queue.push(
+                    out,
+                    EsbuildJob {
+                        lang: EsbuildLang::Js,
+                        cache_key: cfg.cache.as_ref().map(|_| {
+                            let kind = if module { "js-module" } else { "js" };
+                            MinifyCache::key(kind, &(&cfg.js_options, cfg.ascii_only), &code)
+                        }),
+                        code: Arc::new(code),
+                        transform_options: ctx.esbuild_options.js(module).clone(),
+                        source_code: code_in_source.to_vec(),
+                        span,
+                    },
+                );
+                return;
+            };
+            write_js(cfg, out, &code);
+            None
+        }
+    };
+    if let Some(diagnostic_type) = diagnostic_type {
+        ctx.diagnostics.push(Diagnostic {
+            diagnostic_type,
+            code: code_in_source.to_vec(),
+            span,
+        });
+    };
+}
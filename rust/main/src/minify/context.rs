@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::diagnostic::Diagnostic;
+#[cfg(feature = "js-esbuild")]
+use crate::minify::esbuild::EsbuildQueue;
+use crate::minify::whitespace_hints::StylesheetHints;
+use crate::name_map::NameMap;
+#[cfg(feature = "js-esbuild")]
+use minify_html_common::esbuild::EsbuildTransformOptions;
+
+// State for the minification of a single document.
+pub struct MinifyContext {
+    pub base_url: Option<Url>,
+    // Only present if class names should be sorted.
+    pub class_order: Option<HashMap<Vec<u8>, usize>>,
+    pub diagnostics: Vec<Diagnostic>,
+    #[cfg(feature = "js-esbuild")]
+    pub esbuild_queue: EsbuildQueue,
+    #[cfg(feature = "js-esbuild")]
+    pub esbuild_options: EsbuildTransformOptions,
+    // Only present if class names and IDs should be mangled.
+    pub name_map: Option<NameMap>,
+    // Only present if CSS-aware whitespace minification is enabled.
+    pub stylesheet_hints: Option<StylesheetHints>,
+}
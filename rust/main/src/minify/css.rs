@@ -1,40 +1,92 @@
 #[cfg(feature = "js-esbuild")]
 use {
-    crate::minify::esbuild::minify_using_esbuild,
-    esbuild_rs::{
-        Charset, LegalComments, Loader, SourceMap, TransformOptions, TransformOptionsBuilder,
-    },
-    lazy_static::lazy_static,
-    std::sync::Arc,
+    crate::diagnostic::DiagnosticType, crate::minify::esbuild::minify_using_esbuild,
+    esbuild_rs::TransformOptions,
 };
 
 use crate::cfg::Cfg;
+use minify_html_common::ascii::escape_css_non_ascii;
 
-#[cfg(feature = "js-esbuild")]
-lazy_static! {
-    pub static ref MINIFY_CSS_TRANSFORM_OPTIONS: Arc<TransformOptions> = {
-        let mut builder = TransformOptionsBuilder::new();
-        builder.charset = Charset::UTF8;
-        builder.legal_comments = LegalComments::None;
-        builder.loader = Loader::CSS;
-        builder.minify_identifiers = true;
-        builder.minify_syntax = true;
-        builder.minify_whitespace = true;
-        builder.source_map = SourceMap::None;
-        builder.build()
+// Writes CSS that isn't minified. Minified CSS is written by the esbuild queue using the document's esbuild options.
+pub fn write_css(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
+    if cfg.ascii_only {
+        out.extend_from_slice(&escape_css_non_ascii(code));
+    } else {
+        out.extend_from_slice(code);
     };
 }
 
-#[cfg(not(feature = "js-esbuild"))]
-pub fn minify_css(_cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
-    out.extend_from_slice(&code);
+// Returns whether `code` can be safely wrapped in a CSS rule, which requires it to not have any braces, unbalanced
+// brackets or parentheses, or unterminated strings or comments that would affect the wrapper.
+#[cfg(feature = "js-esbuild")]
+pub fn is_well_formed_declaration_list(code: &[u8]) -> bool {
+    let mut closers = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        match code[i] {
+            b'/' if code.get(i + 1) == Some(&b'*') => {
+                match code[i + 2..].windows(2).position(|w| w == b"*/") {
+                    Some(end) => i += end + 3,
+                    None => return false,
+                };
+            }
+            q @ (b'"' | b'\'') => loop {
+                i += 1;
+                match code.get(i) {
+                    None | Some(b'\n' | b'\r' | b'\x0c') => return false,
+                    Some(b'\\') => i += 1,
+                    Some(&c) if c == q => break,
+                    _ => {}
+                };
+            },
+            b'\\' => {
+                if i + 1 == code.len() {
+                    return false;
+                };
+                i += 1;
+            }
+            b'(' => closers.push(b')'),
+            b'[' => closers.push(b']'),
+            c @ (b')' | b']') if closers.pop() != Some(c) => return false,
+            b'{' | b'}' => return false,
+            _ => {}
+        };
+        i += 1;
+    }
+    closers.is_empty()
 }
 
+// Minifies the value of a `style` attribute, which is a CSS declaration list, by wrapping it in a rule.
 #[cfg(feature = "js-esbuild")]
-pub fn minify_css(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
-    if !cfg.minify_css {
-        out.extend_from_slice(&code);
-    } else {
-        minify_using_esbuild(out, code, &MINIFY_CSS_TRANSFORM_OPTIONS.clone());
+pub fn minify_css_declarations(
+    transform_options: &TransformOptions,
+    code: &[u8],
+) -> Result<Vec<u8>, DiagnosticType> {
+    if !is_well_formed_declaration_list(code) {
+        return Err(DiagnosticType::MalformedStyleAttribute);
+    };
+    let mut wrapped = Vec::with_capacity(code.len() + 3);
+    wrapped.extend_from_slice(b"x{");
+    wrapped.extend_from_slice(code);
+    wrapped.push(b'}');
+    let mut min = Vec::with_capacity(wrapped.len());
+    if let Err(mut errors) = minify_using_esbuild(&mut min, &wrapped, transform_options) {
+        // Make positions relative to the attribute value instead of the wrapping rule.
+        for e in errors.iter_mut().filter(|e| e.line == 1) {
+            e.column = e.column.saturating_sub(2);
+        }
+        return Err(DiagnosticType::StyleAttributeMinificationFailed(errors));
+    };
+    // esbuild removes the rule if there are no declarations.
+    if min.is_empty() {
+        return Ok(min);
+    };
+    match min
+        .strip_prefix(b"x{")
+        .and_then(|m| m.strip_suffix(b"}"))
+        .filter(|m| is_well_formed_declaration_list(m))
+    {
+        Some(m) => Ok(m.to_vec()),
+        None => Err(DiagnosticType::StyleAttributeMinificationFailed(Vec::new())),
     }
 }
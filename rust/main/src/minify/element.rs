@@ -1,11 +1,19 @@
 use std::collections::HashMap;
+use std::ops::Range;
 
 use crate::ast::{ElementClosingTag, NodeData};
 use crate::cfg::Cfg;
 use crate::minify::attr::{minify_attr, AttrMinified};
-use crate::minify::content::minify_content;
+use crate::minify::content::{minify_content, minify_script_or_style_content};
+use crate::minify::context::MinifyContext;
+use crate::minify::script_html::minify_html_script;
+use crate::minify::whitespace_hints::get_element_hint;
+use minify_html_common::spec::attr::is_redundant_attribute;
+use minify_html_common::spec::tag::esi::is_esi_tag;
 use minify_html_common::spec::tag::ns::Namespace;
-use minify_html_common::spec::tag::omission::{can_omit_as_before, can_omit_as_last_node};
+use minify_html_common::spec::tag::omission::{
+    can_omit_as_before, can_omit_as_last_node, can_omit_opening_tag,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum LastAttr {
@@ -14,89 +22,169 @@ enum LastAttr {
     Unquoted,
 }
 
+// Where an element is among its parent's children, which determines whether its opening and closing tags can be omitted.
+pub struct ElementPosition<'a> {
+    // Use an empty slice if none.
+    pub parent: &'a [u8],
+    // Use an empty slice if the next element or text sibling node is not an element.
+    pub next_sibling_as_element_tag_name: &'a [u8],
+    // If the last node of the parent is an element and it's this one.
+    pub is_last_child_text_or_element_node: bool,
+    // Use an empty slice if the previous node written is not an element with an omitted closing tag.
+    pub previous_sibling_with_omitted_closing_tag: &'a [u8],
+}
+
+// Returns whether the closing tag was omitted.
 pub fn minify_element(
     cfg: &Cfg,
+    ctx: &mut MinifyContext,
     out: &mut Vec<u8>,
     descendant_of_pre: bool,
     ns: Namespace,
-    // Use an empty slice if none.
-    parent: &[u8],
-    // Use an empty slice if the next element or text sibling node is not an element.
-    next_sibling_as_element_tag_name: &[u8],
-    // If the last node of the parent is an element and it's this one.
-    is_last_child_text_or_element_node: bool,
+    position: ElementPosition,
     tag_name: &[u8],
     attributes: HashMap<Vec<u8>, Vec<u8>>,
     closing_tag: ElementClosingTag,
     children: Vec<NodeData>,
-) {
-    let can_omit_opening_tag = (tag_name == b"html" || tag_name == b"head")
-        && attributes.is_empty()
-        && !cfg.keep_html_and_head_opening_tags;
+    span: Range<usize>,
+) -> bool {
+    let ElementPosition {
+        parent,
+        next_sibling_as_element_tag_name,
+        is_last_child_text_or_element_node,
+        previous_sibling_with_omitted_closing_tag,
+    } = position;
+    let hint = ctx
+        .stylesheet_hints
+        .as_ref()
+        .map(|hints| get_element_hint(hints, tag_name, &attributes))
+        .unwrap_or_default();
+    // ESI elements are processed before the document is parsed as HTML, so their closing tags are never omitted.
     let can_omit_closing_tag = !cfg.keep_closing_tags
+        && !is_esi_tag(tag_name)
         && (can_omit_as_before(tag_name, next_sibling_as_element_tag_name)
             || (is_last_child_text_or_element_node && can_omit_as_last_node(parent, tag_name)));
 
-    // TODO Attributes list could become empty after minification, making opening tag eligible for omission again.
-    if !can_omit_opening_tag {
-        out.push(b'<');
-        out.extend_from_slice(tag_name);
-        let mut last_attr = LastAttr::NoValue;
-        // TODO Further optimisation: order attrs based on optimal spacing strategy, given that spaces can be omitted after quoted attrs, and maybe after the tag name?
-        let mut attrs_sorted = attributes.into_iter().collect::<Vec<_>>();
-        attrs_sorted.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        for (name, value) in attrs_sorted {
-            let min = minify_attr(cfg, ns, tag_name, &name, value);
-            if let AttrMinified::Redundant = min {
-                continue;
-            };
-            if cfg.keep_spaces_between_attributes || last_attr != LastAttr::Quoted {
-                out.push(b' ');
-            };
-            out.extend_from_slice(&name);
-            match min {
-                AttrMinified::NoValue => {
-                    last_attr = LastAttr::NoValue;
-                }
-                AttrMinified::Value(v) => {
-                    debug_assert!(v.len() > 0);
-                    out.push(b'=');
-                    v.out(out);
-                    last_attr = if v.quoted() {
-                        LastAttr::Quoted
-                    } else {
-                        LastAttr::Unquoted
-                    };
-                }
-                _ => unreachable!(),
-            };
-        }
-        if closing_tag == ElementClosingTag::SelfClosing {
-            if last_attr == LastAttr::Unquoted {
-                out.push(b' ');
-            };
-            out.push(b'/');
+    // The opening tag is written and then removed if it turns out it can be omitted, as that can depend on whether
+    // any attributes remain after minification and on the minified content.
+    let opening_tag_start = out.len();
+    out.push(b'<');
+    out.extend_from_slice(tag_name);
+    let mut last_attr = LastAttr::NoValue;
+    // TODO Further optimisation: order attrs based on optimal spacing strategy, given that spaces can be omitted after quoted attrs, and maybe after the tag name?
+    // Some attributes are redundant depending on the other attributes, so check them before any are minified.
+    let redundant_attrs = if ns == Namespace::Html {
+        attributes
+            .iter()
+            .filter(|(name, value)| {
+                is_redundant_attribute(
+                    |c| cfg.keep_redundant_attributes.contains(&c),
+                    tag_name,
+                    name,
+                    value,
+                    |other| attributes.get(other).map(|v| v.as_slice()),
+                )
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let is_html_script = ns == Namespace::Html
+        && tag_name == b"script"
+        && attributes
+            .get(b"type".as_ref())
+            .filter(|t| cfg.html_script_types.contains(*t))
+            .is_some();
+    let mut attrs_sorted = attributes
+        .into_iter()
+        .filter(|(name, _)| !redundant_attrs.contains(name))
+        .collect::<Vec<_>>();
+    attrs_sorted.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    for (name, value) in attrs_sorted {
+        let min = minify_attr(cfg, ctx, ns, tag_name, &name, value, &span);
+        if let AttrMinified::Redundant = min {
+            continue;
+        };
+        if cfg.keep_spaces_between_attributes || last_attr != LastAttr::Quoted {
+            out.push(b' ');
+        };
+        out.extend_from_slice(&name);
+        match min {
+            AttrMinified::NoValue => {
+                last_attr = LastAttr::NoValue;
+            }
+            AttrMinified::Value(v) => {
+                debug_assert!(v.len() > 0);
+                out.push(b'=');
+                v.out(out);
+                last_attr = if v.quoted() {
+                    LastAttr::Quoted
+                } else {
+                    LastAttr::Unquoted
+                };
+            }
+            _ => unreachable!(),
         };
-        out.push(b'>');
     }
+    let has_attrs = out.len() > opening_tag_start + 1 + tag_name.len();
+    if closing_tag == ElementClosingTag::SelfClosing {
+        if last_attr == LastAttr::Unquoted {
+            out.push(b' ');
+        };
+        out.push(b'/');
+    };
+    out.push(b'>');
 
     if closing_tag == ElementClosingTag::SelfClosing || closing_tag == ElementClosingTag::Void {
         debug_assert!(children.is_empty());
-        return;
+        return false;
     };
+    let content_start = out.len();
 
-    minify_content(
-        cfg,
-        out,
-        descendant_of_pre || (ns == Namespace::Html && tag_name == b"pre"),
-        tag_name,
-        children,
-    );
+    match children.first() {
+        Some(NodeData::ScriptOrStyleContent {
+            code,
+            span: code_span,
+            ..
+        }) if is_html_script => minify_html_script(cfg, ctx, out, code, code_span.start),
+        // The content of a `<script>` or `<style>` is always its only child.
+        Some(NodeData::ScriptOrStyleContent { code, lang, .. }) => {
+            minify_script_or_style_content(cfg, ctx, out, code, *lang, span)
+        }
+        _ => minify_content(
+            cfg,
+            ctx,
+            out,
+            descendant_of_pre || (ns == Namespace::Html && tag_name == b"pre") || hint.preserve,
+            tag_name,
+            hint.inline,
+            children,
+        ),
+    };
+
+    let can_omit_opening_tag = !has_attrs
+        && ns == Namespace::Html
+        && match tag_name {
+            b"html" | b"head" => !cfg.keep_html_and_head_opening_tags,
+            _ => can_omit_opening_tag(
+                tag_name,
+                previous_sibling_with_omitted_closing_tag,
+                &out[content_start..],
+            ),
+        };
+    if can_omit_opening_tag {
+        out.drain(opening_tag_start..content_start);
+        #[cfg(feature = "js-esbuild")]
+        ctx.esbuild_queue
+            .remove_output(opening_tag_start..content_start);
+    };
 
     if closing_tag != ElementClosingTag::Present || can_omit_closing_tag {
-        return;
+        return true;
     };
     out.extend_from_slice(b"</");
     out.extend_from_slice(tag_name);
     out.push(b'>');
+    false
 }
@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use crate::ast::{ElementClosingTag, NodeData};
+use crate::cfg::Cfg;
+use crate::minify::comment::is_kept_comment;
+use crate::minify::content::get_whitespace_minification;
+use crate::minify::context::MinifyContext;
+use crate::minify::merge::append_nodes;
+use crate::minify::whitespace_hints::get_element_hint;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::whitespace::is_all_whitespace;
+
+// Elements that don't render anything or have any other effect when they have no attributes and no content.
+// This excludes elements like `td`, `iframe`, `textarea`, `q`, and `li` that are rendered even when empty, `script` and
+// `template` whose content isn't rendered, and custom elements, which could do anything.
+pub fn default_removable_empty_elements() -> HashSet<Vec<u8>> {
+    [
+        "a", "abbr", "b", "bdi", "cite", "code", "del", "dfn", "div", "em", "i", "ins", "kbd",
+        "mark", "p", "s", "samp", "small", "span", "strong", "sub", "sup", "u", "var",
+    ]
+    .iter()
+    .map(|t| t.as_bytes().to_vec())
+    .collect()
+}
+
+// Whether the node is an element that would have no attributes and no content after minification, including after
+// whitespace is minified and any descendant elements that are also empty are removed.
+fn is_removable_empty_element(
+    cfg: &Cfg,
+    ctx: &MinifyContext,
+    descendant_of_pre: bool,
+    node: &NodeData,
+) -> bool {
+    let (attributes, children, closing_tag, name, namespace) = match node {
+        NodeData::Element {
+            attributes,
+            children,
+            closing_tag,
+            name,
+            namespace,
+            ..
+        } => (attributes, children, closing_tag, name, namespace),
+        _ => return false,
+    };
+    if *namespace != Namespace::Html
+        || !attributes.is_empty()
+        || *closing_tag == ElementClosingTag::Void
+        || !cfg.removable_empty_elements.contains(name)
+    {
+        return false;
+    };
+    let hint = ctx
+        .stylesheet_hints
+        .as_ref()
+        .map(|hints| get_element_hint(hints, name, attributes))
+        .unwrap_or_default();
+    let descendant_of_pre = descendant_of_pre || name == b"pre" || hint.preserve;
+    let whitespace =
+        get_whitespace_minification(cfg, ctx, descendant_of_pre, name, hint.inline, children);
+    children.iter().all(|c| match c {
+        NodeData::Comment { code, .. } => !is_kept_comment(cfg, code),
+        NodeData::Text { value } => {
+            is_all_whitespace(value) && (whitespace.destroy_whole || whitespace.trim)
+        }
+        NodeData::Element { .. } => is_removable_empty_element(cfg, ctx, descendant_of_pre, c),
+        _ => false,
+    })
+}
+
+// Removes elements that would be empty after minification, then joins any text nodes that are now adjacent.
+pub fn remove_empty_elements(
+    cfg: &Cfg,
+    ctx: &MinifyContext,
+    descendant_of_pre: bool,
+    nodes: &mut Vec<NodeData>,
+) {
+    let len = nodes.len();
+    nodes.retain(|n| !is_removable_empty_element(cfg, ctx, descendant_of_pre, n));
+    if nodes.len() == len {
+        return;
+    };
+    let remaining = std::mem::take(nodes);
+    append_nodes(nodes, remaining);
+}
@@ -1,18 +1,217 @@
 #[cfg(feature = "js-esbuild")]
-use {crossbeam::sync::WaitGroup, esbuild_rs::TransformOptions};
+use {
+    crate::cache::MinifyCache,
+    crate::cfg::Cfg,
+    crate::diagnostic::{Diagnostic, DiagnosticType, SyntaxError},
+    crate::minify::css::write_css,
+    crate::minify::js::write_js,
+    crossbeam::sync::WaitGroup,
+    esbuild_rs::{TransformOptions, TransformResult},
+    std::ops::Range,
+    std::sync::mpsc::{channel, Receiver, Sender},
+    std::sync::Arc,
+};
+
+// The minified code, or the errors reported by esbuild.
+#[cfg(feature = "js-esbuild")]
+type EsbuildResult = Result<Vec<u8>, Vec<SyntaxError>>;
+
+#[cfg(feature = "js-esbuild")]
+fn get_result(result: &TransformResult) -> EsbuildResult {
+    let errors = result.errors.as_slice();
+    if !errors.is_empty() {
+        return Err(errors
+            .iter()
+            .map(|e| SyntaxError {
+                line: e.line as usize,
+                column: e.column as usize,
+                message: e.text.as_str().to_string(),
+            })
+            .collect());
+    };
+    Ok(result.code.as_str().trim().as_bytes().to_vec())
+}
 
 #[cfg(feature = "js-esbuild")]
 // TODO The use of WG is ugly and we don't want to be multi-threaded; wait for Rust port esbuild-transform-rs.
-pub fn minify_using_esbuild(out: &mut Vec<u8>, code: &[u8], transform_options: &TransformOptions) {
+// Nothing is written if esbuild reported any errors, as the output could be empty or truncated.
+pub fn minify_using_esbuild(
+    out: &mut Vec<u8>,
+    code: &[u8],
+    transform_options: &TransformOptions,
+) -> Result<(), Vec<SyntaxError>> {
     let wg = WaitGroup::new();
+    let mut res = Ok(Vec::new());
     unsafe {
         let wg = wg.clone();
+        let res = &mut res;
         // esbuild now officially handles escaping `</script` and `</style`.
         esbuild_rs::transform_direct_unmanaged(code, transform_options, move |result| {
-            let min_code = result.code.as_str().trim().as_bytes();
-            out.extend_from_slice(min_code);
+            *res = get_result(&result);
             drop(wg);
         });
     };
     wg.wait();
+    out.extend_from_slice(&res?);
+    Ok(())
+}
+
+#[cfg(feature = "js-esbuild")]
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum EsbuildLang {
+    Css,
+    Js,
+}
+
+#[cfg(feature = "js-esbuild")]
+pub struct EsbuildJob {
+    pub lang: EsbuildLang,
+    // The code to minify, which is written as is if esbuild reports errors.
+    pub code: Arc<Vec<u8>>,
+    pub transform_options: Arc<TransformOptions>,
+    // The code in the source and the position of its element, for diagnostics.
+    pub source_code: Vec<u8>,
+    pub span: Range<usize>,
+    // Only present if a cache is used.
+    pub cache_key: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "js-esbuild")]
+struct QueuedJob {
+    job: EsbuildJob,
+    // Where the result should be inserted in the output, or None if the output containing it was discarded.
+    position: Option<usize>,
+    result: Option<EsbuildResult>,
+}
+
+// Minifies the content of `<script>` and `<style>` tags in parallel using esbuild while the rest of the document is
+// minified, instead of waiting for each one. The output is written with the results left out, and they're inserted
+// once they're all done.
+#[cfg(feature = "js-esbuild")]
+pub struct EsbuildQueue {
+    cache: Option<Arc<MinifyCache>>,
+    max_running: usize,
+    running: usize,
+    jobs: Vec<QueuedJob>,
+    sender: Sender<(usize, EsbuildResult)>,
+    receiver: Receiver<(usize, EsbuildResult)>,
+}
+
+#[cfg(feature = "js-esbuild")]
+impl EsbuildQueue {
+    pub fn new(cfg: &Cfg) -> EsbuildQueue {
+        let (sender, receiver) = channel();
+        EsbuildQueue {
+            cache: cfg.cache.clone(),
+            max_running: match cfg.esbuild_threads {
+                0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            },
+            running: 0,
+            jobs: Vec::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    fn receive(&mut self) {
+        let (i, result) = self.receiver.recv().unwrap();
+        self.jobs[i].result = Some(result);
+        self.running -= 1;
+    }
+
+    // Starts minifying the code, whose result will be inserted at the current end of `out`.
+    pub fn push(&mut self, out: &[u8], mut job: EsbuildJob) {
+        let cached = match (&self.cache, &job.cache_key) {
+            (Some(cache), Some(key)) => cache.get(key),
+            _ => None,
+        };
+        if let Some(min) = cached {
+            job.cache_key = None;
+            self.jobs.push(QueuedJob {
+                job,
+                position: Some(out.len()),
+                result: Some(Ok(min)),
+            });
+            return;
+        };
+        while self.running >= self.max_running {
+            self.receive();
+        }
+        let i = self.jobs.len();
+        let sender = self.sender.clone();
+        esbuild_rs::transform_direct(
+            job.code.clone(),
+            job.transform_options.clone(),
+            move |result| {
+                // The receiver is only dropped before all results are received if minification panicked.
+                let _ = sender.send((i, get_result(&result)));
+            },
+        );
+        self.running += 1;
+        self.jobs.push(QueuedJob {
+            job,
+            position: Some(out.len()),
+            result: None,
+        });
+    }
+
+    // Must be called when the output at `range` is removed.
+    pub fn remove_output(&mut self, range: Range<usize>) {
+        for j in self.jobs.iter_mut() {
+            j.position = match j.position {
+                Some(p) if p >= range.end => Some(p - range.len()),
+                Some(p) if p > range.start => None,
+                p => p,
+            };
+        }
+    }
+
+    // Waits for all jobs to finish and inserts their results into `out`, or the code as is if esbuild reported errors.
+    pub fn finish(
+        &mut self,
+        cfg: &Cfg,
+        out: Vec<u8>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Vec<u8> {
+        while self.running > 0 {
+            self.receive();
+        }
+        let mut with_results = Vec::with_capacity(out.len());
+        let mut next = 0;
+        for j in std::mem::take(&mut self.jobs) {
+            let position = match j.position {
+                Some(p) => p,
+                None => continue,
+            };
+            with_results.extend_from_slice(&out[next..position]);
+            next = position;
+            let res = j.result.unwrap();
+            if let (Some(cache), Some(key), Ok(min)) = (&self.cache, j.job.cache_key, &res) {
+                cache.insert(key, min.clone());
+            };
+            let min = match &res {
+                Ok(min) => min,
+                Err(_) => j.job.code.as_ref(),
+            };
+            match j.job.lang {
+                EsbuildLang::Css => write_css(cfg, &mut with_results, min),
+                EsbuildLang::Js => write_js(cfg, &mut with_results, min),
+            };
+            if let Err(errors) = res {
+                diagnostics.push(Diagnostic {
+                    diagnostic_type: match j.job.lang {
+                        EsbuildLang::Css => DiagnosticType::StyleMinificationFailed(errors),
+                        EsbuildLang::Js => DiagnosticType::ScriptMinificationFailed(errors),
+                    },
+                    code: j.job.source_code,
+                    span: j.job.span,
+                });
+            };
+        }
+        with_results.extend_from_slice(&out[next..]);
+        // Keep diagnostics in source order, as the ones for jobs are added last.
+        diagnostics.sort_by_key(|d| d.span.start);
+        with_results
+    }
 }
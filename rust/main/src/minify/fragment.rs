@@ -0,0 +1,66 @@
+use crate::cfg::Cfg;
+use crate::minify::content::minify_content;
+use crate::minify::context::MinifyContext;
+#[cfg(feature = "js-esbuild")]
+use crate::minify::esbuild::EsbuildQueue;
+use crate::parse::content::parse_content;
+use crate::parse::Code;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::EMPTY_SLICE;
+
+// Minifies HTML that's part of a larger document, such as the content of a conditional comment or a `<script>` tag
+// containing a template, so closing tags are kept. The HTML is written as is if the minified HTML contains
+// `terminator`, which could come from decoded entities or minified scripts and would end the comment or tag containing
+// the HTML early. `html_start` is the position of the HTML in the source.
+pub fn minify_html_fragment(
+    cfg: &Cfg,
+    ctx: &mut MinifyContext,
+    out: &mut Vec<u8>,
+    descendant_of_pre: bool,
+    html: &[u8],
+    html_start: usize,
+    terminator: &[u8],
+) {
+    let parsed = parse_content(
+        &mut Code::new_at(html, html_start),
+        Namespace::Html,
+        EMPTY_SLICE,
+        EMPTY_SLICE,
+    );
+    let inner_cfg = Cfg {
+        keep_closing_tags: true,
+        ..cfg.clone()
+    };
+    // The minified `<script>` and `<style>` tags in the HTML must be inserted before checking for `terminator`, so
+    // they're minified using a separate queue that's finished here.
+    #[cfg(feature = "js-esbuild")]
+    let outer_esbuild_queue = std::mem::replace(&mut ctx.esbuild_queue, EsbuildQueue::new(cfg));
+    let mut min = Vec::new();
+    minify_content(
+        &inner_cfg,
+        ctx,
+        &mut min,
+        descendant_of_pre,
+        EMPTY_SLICE,
+        false,
+        parsed.children,
+    );
+    #[cfg(feature = "js-esbuild")]
+    let mut diagnostics = Vec::new();
+    #[cfg(feature = "js-esbuild")]
+    let min = {
+        let min = ctx.esbuild_queue.finish(cfg, min, &mut diagnostics);
+        ctx.esbuild_queue = outer_esbuild_queue;
+        min
+    };
+    if min
+        .windows(terminator.len())
+        .any(|w| w.eq_ignore_ascii_case(terminator))
+    {
+        out.extend_from_slice(html);
+    } else {
+        out.extend_from_slice(&min);
+        #[cfg(feature = "js-esbuild")]
+        ctx.diagnostics.extend(diagnostics);
+    };
+}
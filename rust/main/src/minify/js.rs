@@ -1,37 +1,58 @@
 #[cfg(feature = "js-esbuild")]
-use {
-    crate::minify::esbuild::minify_using_esbuild,
-    esbuild_rs::{Charset, LegalComments, SourceMap, TransformOptions, TransformOptionsBuilder},
-    lazy_static::lazy_static,
-    std::sync::Arc,
-};
+use {crate::minify::esbuild::minify_using_esbuild, esbuild_rs::TransformOptions};
 
 use crate::Cfg;
+use minify_html_common::ascii::escape_js_non_ascii;
 
+// Minifies code that is run as the body of a function, such as the value of an event handler attribute.
+// Returns None if esbuild failed or changed the wrapping function, in which case the code should be left as is.
 #[cfg(feature = "js-esbuild")]
-lazy_static! {
-    static ref TRANSFORM_OPTIONS: Arc<TransformOptions> = {
-        let mut builder = TransformOptionsBuilder::new();
-        builder.charset = Charset::UTF8;
-        builder.legal_comments = LegalComments::None;
-        builder.minify_identifiers = true;
-        builder.minify_syntax = true;
-        builder.minify_whitespace = true;
-        builder.source_map = SourceMap::None;
-        builder.build()
-    };
+pub fn minify_js_function_body(
+    transform_options: &TransformOptions,
+    code: &[u8],
+) -> Option<Vec<u8>> {
+    let mut wrapped = Vec::with_capacity(code.len() + 15);
+    wrapped.extend_from_slice(b"function x(){");
+    wrapped.extend_from_slice(code);
+    // Use a newline in case the code ends with a single-line comment.
+    wrapped.extend_from_slice(b"\n}");
+    let mut min = Vec::new();
+    minify_using_esbuild(&mut min, &wrapped, transform_options).ok()?;
+    Some(
+        min.strip_prefix(b"function x(){")?
+            .strip_suffix(b"}")?
+            .to_vec(),
+    )
 }
 
-#[cfg(not(feature = "js-esbuild"))]
-pub fn minify_js(_cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
-    out.extend_from_slice(&code);
+// Minifies the script in a `javascript:` URL, which is run as a script whose completion value replaces the document
+// if it's a string, so `transform_options` must not minify syntax. Returns None if esbuild failed or the result isn't
+// shorter, in which case the code should be left as is.
+#[cfg(feature = "js-esbuild")]
+pub fn minify_js_url_script(transform_options: &TransformOptions, code: &[u8]) -> Option<Vec<u8>> {
+    // The URL is percent-decoded and has tabs and newlines removed before it's run, so the code esbuild sees would
+    // differ from the code that's run.
+    let is_unsafe = |code: &[u8]| {
+        code.iter()
+            .any(|c| matches!(c, b'%' | b'\t' | b'\n' | b'\r'))
+    };
+    if is_unsafe(code) {
+        return None;
+    };
+    let mut min = Vec::new();
+    minify_using_esbuild(&mut min, code, transform_options).ok()?;
+    // esbuild can write these too, such as when unescaping string literals or keeping legal comments.
+    if is_unsafe(&min) || min.len() >= code.len() {
+        return None;
+    };
+    Some(min)
 }
 
-#[cfg(feature = "js-esbuild")]
-pub fn minify_js(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
-    if !cfg.minify_js {
-        out.extend_from_slice(&code);
+// Writes JS that isn't minified. Minified JS is written by the esbuild queue using the document's esbuild options.
+pub fn write_js(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
+    if cfg.ascii_only {
+        out.extend_from_slice(&escape_js_non_ascii(code));
     } else {
-        minify_using_esbuild(out, code, &TRANSFORM_OPTIONS.clone());
-    }
+        out.extend_from_slice(code);
+    };
 }
@@ -0,0 +1,165 @@
+// Deeper nesting is treated as malformed to avoid overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+struct JsonMinifier<'c> {
+    code: &'c [u8],
+    next: usize,
+    out: Vec<u8>,
+}
+
+impl<'c> JsonMinifier<'c> {
+    fn peek(&self) -> Option<u8> {
+        self.code.get(self.next).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        // JSON only allows space, tab, line feed, and carriage return.
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.next += 1;
+        }
+    }
+
+    fn accept_literal(&mut self, literal: &[u8]) -> Option<()> {
+        if !self.code[self.next..].starts_with(literal) {
+            return None;
+        };
+        self.out.extend_from_slice(literal);
+        self.next += literal.len();
+        Some(())
+    }
+
+    fn accept_digits(&mut self) -> Option<()> {
+        let start = self.next;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.next += 1;
+        }
+        if self.next == start {
+            return None;
+        };
+        self.out.extend_from_slice(&self.code[start..self.next]);
+        Some(())
+    }
+
+    fn accept_number(&mut self) -> Option<()> {
+        if self.peek() == Some(b'-') {
+            self.accept_literal(b"-")?;
+        };
+        if self.peek() == Some(b'0') {
+            self.accept_literal(b"0")?;
+        } else {
+            self.accept_digits()?;
+        };
+        if self.peek() == Some(b'.') {
+            self.accept_literal(b".")?;
+            self.accept_digits()?;
+        };
+        if let Some(c @ (b'e' | b'E')) = self.peek() {
+            self.out.push(c);
+            self.next += 1;
+            if let Some(c @ (b'+' | b'-')) = self.peek() {
+                self.out.push(c);
+                self.next += 1;
+            };
+            self.accept_digits()?;
+        };
+        Some(())
+    }
+
+    // Strings are copied as is, except that `</script` and `<!--` are escaped so that they can't end the `<script>`
+    // or change how its end is found.
+    fn accept_string(&mut self) -> Option<()> {
+        self.accept_literal(b"\"")?;
+        loop {
+            let c = self.peek()?;
+            let rem = &self.code[self.next..];
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let len = match rem.get(1)? {
+                        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => 2,
+                        b'u' if rem.len() >= 6 && rem[2..6].iter().all(u8::is_ascii_hexdigit) => 6,
+                        _ => return None,
+                    };
+                    self.out.extend_from_slice(&rem[..len]);
+                    self.next += len;
+                    continue;
+                }
+                0..=0x1f => return None,
+                b'<' if rem.len() >= 8 && rem[..8].eq_ignore_ascii_case(b"</script") => {
+                    self.out.extend_from_slice(b"<\\/");
+                    self.next += 2;
+                    continue;
+                }
+                b'<' if rem.starts_with(b"<!--") => {
+                    self.out.extend_from_slice(b"\\u003C");
+                }
+                _ => self.out.push(c),
+            };
+            self.next += 1;
+        }
+        self.accept_literal(b"\"")
+    }
+
+    fn accept_value(&mut self, depth: usize) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        };
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => {
+                self.accept_literal(b"{")?;
+                self.skip_whitespace();
+                if self.peek() != Some(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        self.accept_string()?;
+                        self.skip_whitespace();
+                        self.accept_literal(b":")?;
+                        self.accept_value(depth + 1)?;
+                        if self.peek() != Some(b',') {
+                            break;
+                        };
+                        self.accept_literal(b",")?;
+                    }
+                };
+                self.accept_literal(b"}")?;
+            }
+            b'[' => {
+                self.accept_literal(b"[")?;
+                self.skip_whitespace();
+                if self.peek() != Some(b']') {
+                    loop {
+                        self.accept_value(depth + 1)?;
+                        if self.peek() != Some(b',') {
+                            break;
+                        };
+                        self.accept_literal(b",")?;
+                    }
+                };
+                self.accept_literal(b"]")?;
+            }
+            b'"' => self.accept_string()?,
+            b't' => self.accept_literal(b"true")?,
+            b'f' => self.accept_literal(b"false")?,
+            b'n' => self.accept_literal(b"null")?,
+            _ => self.accept_number()?,
+        };
+        self.skip_whitespace();
+        Some(())
+    }
+}
+
+// Removes all whitespace outside of strings from valid JSON, such as the contents of a `<script type="application/ld+json">`
+// tag. Returns None if the code isn't valid JSON.
+pub fn minify_json(code: &[u8]) -> Option<Vec<u8>> {
+    let mut minifier = JsonMinifier {
+        code,
+        next: 0,
+        out: Vec::with_capacity(code.len()),
+    };
+    minifier.accept_value(0)?;
+    if minifier.next != code.len() {
+        return None;
+    };
+    Some(minifier.out)
+}
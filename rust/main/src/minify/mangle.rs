@@ -0,0 +1,500 @@
+use std::char::from_u32;
+use std::collections::{HashMap, HashSet};
+use std::str::from_utf8;
+
+use crate::ast::{NodeData, ScriptOrStyleLang};
+use crate::name_map::NameMap;
+use minify_html_common::gen::codepoints::WHITESPACE;
+use minify_html_common::spec::tag::ns::Namespace;
+
+// Attributes whose value is a space-separated list of IDs of other elements.
+// See https://html.spec.whatwg.org/multipage/indices.html#attributes-3 and https://www.w3.org/TR/wai-aria-1.2/#idref.
+pub fn is_id_ref_attr(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"for"
+            | b"form"
+            | b"headers"
+            | b"list"
+            | b"popovertarget"
+            | b"aria-activedescendant"
+            | b"aria-controls"
+            | b"aria-describedby"
+            | b"aria-details"
+            | b"aria-errormessage"
+            | b"aria-flowto"
+            | b"aria-labelledby"
+            | b"aria-owns"
+    )
+}
+
+// SVG presentation attributes whose value can reference another element like `url(#id)`.
+// See https://www.w3.org/TR/SVG2/styling.html#PresentationAttributes.
+pub fn is_url_ref_presentation_attr(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"clip-path"
+            | b"cursor"
+            | b"fill"
+            | b"filter"
+            | b"marker-end"
+            | b"marker-mid"
+            | b"marker-start"
+            | b"mask"
+            | b"stroke"
+    )
+}
+
+fn lookup<'a>(map: &'a HashMap<String, String>, name: &[u8]) -> Option<&'a [u8]> {
+    from_utf8(name)
+        .ok()
+        .and_then(|name| map.get(name))
+        .map(|m| m.as_bytes())
+}
+
+pub fn mangled_class<'a>(map: &'a NameMap, name: &'a [u8]) -> &'a [u8] {
+    lookup(&map.classes, name).unwrap_or(name)
+}
+
+pub fn mangled_id<'a>(map: &'a NameMap, name: &'a [u8]) -> &'a [u8] {
+    lookup(&map.ids, name).unwrap_or(name)
+}
+
+// Maps each ID in a space-separated list, collapsing the whitespace between them.
+pub fn mangle_id_refs(map: &NameMap, value: &[u8]) -> Vec<u8> {
+    value
+        .split(|&c| WHITESPACE[c])
+        .filter(|id| !id.is_empty())
+        .map(|id| mangled_id(map, id))
+        .collect::<Vec<_>>()
+        .join(b" ".as_ref())
+}
+
+#[derive(Default)]
+struct DocumentNames<'a> {
+    // How many times each name is used in `class` and `id` attributes.
+    classes: HashMap<&'a [u8], usize>,
+    ids: HashMap<&'a [u8], usize>,
+    // Names referenced in CSS selectors, `url(#id)` references, and attributes that reference IDs. Those that aren't used
+    // in `class` and `id` attributes aren't renamed, so they can't be used as new names.
+    referenced_classes: HashSet<Vec<u8>>,
+    referenced_ids: HashSet<Vec<u8>>,
+}
+
+impl DocumentNames<'_> {
+    fn add_css_references(&mut self, code: &[u8], declarations: bool) {
+        mangle_css_in(code, declarations, &mut |kind, name| {
+            match kind {
+                NameKind::Class => self.referenced_classes.insert(name.to_vec()),
+                NameKind::Id => self.referenced_ids.insert(name.to_vec()),
+            };
+            None
+        });
+    }
+}
+
+fn count_names<'a>(names: &mut DocumentNames<'a>, nodes: &'a [NodeData]) {
+    for n in nodes {
+        match n {
+            NodeData::Element {
+                attributes,
+                children,
+                namespace,
+                ..
+            } => {
+                for (name, value) in attributes.iter() {
+                    let name = name.as_slice();
+                    match name {
+                        b"class" => {
+                            for c in value.split(|&c| WHITESPACE[c]).filter(|c| !c.is_empty()) {
+                                *names.classes.entry(c).or_default() += 1;
+                            }
+                        }
+                        b"id" if !value.is_empty() => *names.ids.entry(value).or_default() += 1,
+                        b"href" | b"xlink:href" if value.starts_with(b"#") => {
+                            names.referenced_ids.insert(value[1..].to_vec());
+                        }
+                        b"style" => names.add_css_references(value, true),
+                        _ if *namespace == Namespace::Html && is_id_ref_attr(name) => {
+                            for id in value.split(|&c| WHITESPACE[c]).filter(|id| !id.is_empty()) {
+                                names.referenced_ids.insert(id.to_vec());
+                            }
+                        }
+                        _ if *namespace == Namespace::Svg && is_url_ref_presentation_attr(name) => {
+                            names.add_css_references(value, true)
+                        }
+                        _ => {}
+                    };
+                }
+                count_names(names, children);
+            }
+            NodeData::ScriptOrStyleContent {
+                code,
+                lang: ScriptOrStyleLang::CSS,
+                ..
+            } => names.add_css_references(code, false),
+            _ => {}
+        };
+    }
+}
+
+// Generates the `n`th shortest name that is a valid CSS identifier without escaping.
+fn generate_name(mut n: usize) -> String {
+    const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-";
+    let mut name = vec![FIRST[n % FIRST.len()]];
+    n /= FIRST.len();
+    while n > 0 {
+        n -= 1;
+        name.push(REST[n % REST.len()]);
+        n /= REST.len();
+    }
+    String::from_utf8(name).unwrap()
+}
+
+// Adds a mangled name for every name in `counts` that isn't already in `map`. More frequent names get shorter names.
+// Names in `referenced` that won't be renamed aren't used as new names.
+fn assign_names(
+    map: &mut HashMap<String, String>,
+    counts: HashMap<&[u8], usize>,
+    referenced: HashSet<Vec<u8>>,
+) {
+    let mut used = map.values().cloned().collect::<HashSet<_>>();
+    used.extend(
+        referenced
+            .into_iter()
+            .filter(|name| !counts.contains_key(name.as_slice()))
+            .filter_map(|name| String::from_utf8(name).ok())
+            .filter(|name| !map.contains_key(name)),
+    );
+    let mut names = counts
+        .into_iter()
+        .filter_map(|(name, count)| from_utf8(name).ok().map(|name| (name, count)))
+        .filter(|(name, _)| !map.contains_key(*name))
+        .collect::<Vec<_>>();
+    names.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let mut next = 0;
+    for (name, _) in names {
+        let mangled = loop {
+            let candidate = generate_name(next);
+            next += 1;
+            if !used.contains(&candidate) {
+                break candidate;
+            };
+        };
+        used.insert(mangled.clone());
+        map.insert(name.to_string(), mangled);
+    }
+}
+
+// Adds mangled names for all class names and IDs used in the document.
+pub fn add_document_names(map: &mut NameMap, nodes: &[NodeData]) {
+    let mut names = DocumentNames::default();
+    count_names(&mut names, nodes);
+    assign_names(&mut map.classes, names.classes, names.referenced_classes);
+    assign_names(&mut map.ids, names.ids, names.referenced_ids);
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80
+}
+
+// Returns the length of the string starting at `code[0]`, including its quotes, or the rest of the code if unterminated.
+fn string_len(code: &[u8]) -> usize {
+    let quote = code[0];
+    let mut i = 1;
+    while i < code.len() {
+        match code[i] {
+            b'\\' => i += 1,
+            c if c == quote => return i + 1,
+            _ => {}
+        };
+        i += 1;
+    }
+    code.len()
+}
+
+// Returns the length of the CSS identifier starting at `code[0]` and its value with escapes like `\\:` and `\\31 `
+// decoded, as class names and IDs in attributes aren't escaped.
+fn css_ident(code: &[u8]) -> (usize, Vec<u8>) {
+    let mut value = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        match code[i] {
+            b'\\'
+                if code
+                    .get(i + 1)
+                    .filter(|&&c| !matches!(c, b'\n' | b'\r' | b'\x0c'))
+                    .is_some() =>
+            {
+                let hex_len = code[i + 1..]
+                    .iter()
+                    .take(6)
+                    .take_while(|c| c.is_ascii_hexdigit())
+                    .count();
+                if hex_len == 0 {
+                    value.push(code[i + 1]);
+                    i += 2;
+                    continue;
+                };
+                let hex = from_utf8(&code[i + 1..i + 1 + hex_len]).unwrap();
+                let decoded = from_u32(u32::from_str_radix(hex, 16).unwrap())
+                    .filter(|&c| c != '\0')
+                    .unwrap_or('\u{FFFD}');
+                value.extend_from_slice(decoded.encode_utf8(&mut [0; 4]).as_bytes());
+                i += 1 + hex_len;
+                // A whitespace character after a hex escape is part of the escape.
+                if code[i..].starts_with(b"\r\n") {
+                    i += 2;
+                } else if code.get(i).filter(|&&c| WHITESPACE[c]).is_some() {
+                    i += 1;
+                };
+            }
+            c if is_ident_char(c) => {
+                value.push(c);
+                i += 1;
+            }
+            _ => break,
+        };
+    }
+    (i, value)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum NameKind {
+    Class,
+    Id,
+}
+
+// Called with each class name and ID in CSS, returning the name to replace it with, if any.
+type Rename<'a> = dyn FnMut(NameKind, &[u8]) -> Option<Vec<u8>> + 'a;
+
+// If `code` starts with a reference to an ID like `url(#id)` or `url("#id")` that's renamed, writes it with the new ID
+// and returns the length of code written.
+fn mangle_css_url_ref(rename: &mut Rename, code: &[u8], out: &mut Vec<u8>) -> Option<usize> {
+    if !code.get(..4)?.eq_ignore_ascii_case(b"url(") {
+        return None;
+    };
+    let mut i = 4 + code[4..].iter().take_while(|&&c| WHITESPACE[c]).count();
+    let quote = code.get(i).copied().filter(|&c| c == b'"' || c == b'\'');
+    if quote.is_some() {
+        i += 1;
+    };
+    if code.get(i) != Some(&b'#') {
+        return None;
+    };
+    i += 1;
+    let (len, id) = css_ident(&code[i..]);
+    let mangled = rename(NameKind::Id, &id)?;
+    out.extend_from_slice(&code[..i]);
+    out.extend_from_slice(&mangled);
+    i += len;
+    if let Some(quote) = quote {
+        // Also write the closing quote so it isn't treated as the start of a string.
+        if code.get(i) == Some(&quote) {
+            out.push(quote);
+            i += 1;
+        };
+    };
+    Some(i)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Prelude {
+    Selector,
+    // An at-rule like `@media` whose block contains rules.
+    ConditionalAtRule,
+    // An at-rule like `@font-face` whose block contains declarations, or a statement like `@import`.
+    OtherAtRule,
+}
+
+// Renames class and ID selectors in CSS, such as the contents of a `<style>` tag, as well as references to IDs like
+// `url(#id)`. Declarations aren't otherwise changed, so values like `#fff` are left as is.
+pub fn mangle_css(map: &NameMap, code: &[u8]) -> Vec<u8> {
+    mangle_css_in(code, false, &mut |kind, name| map_name(map, kind, name))
+}
+
+// Renames references to IDs like `url(#id)` in CSS declarations, such as the value of a `style` attribute or an SVG
+// presentation attribute like `fill`.
+pub fn mangle_css_declarations(map: &NameMap, code: &[u8]) -> Vec<u8> {
+    mangle_css_in(code, true, &mut |kind, name| map_name(map, kind, name))
+}
+
+fn map_name(map: &NameMap, kind: NameKind, name: &[u8]) -> Option<Vec<u8>> {
+    let names = match kind {
+        NameKind::Class => &map.classes,
+        NameKind::Id => &map.ids,
+    };
+    lookup(names, name).map(|m| m.to_vec())
+}
+
+fn mangle_css_in(code: &[u8], declarations: bool, rename: &mut Rename) -> Vec<u8> {
+    let mut out = Vec::with_capacity(code.len());
+    // Whether each enclosing block contains declarations rather than rules.
+    let mut blocks = vec![declarations];
+    let mut prelude = Prelude::Selector;
+    let mut i = 0;
+    while i < code.len() {
+        let c = code[i];
+        let in_declarations = *blocks.last().unwrap();
+        match c {
+            b'"' | b'\'' => {
+                let len = string_len(&code[i..]);
+                out.extend_from_slice(&code[i..i + len]);
+                i += len;
+                continue;
+            }
+            b'/' if code[i..].starts_with(b"/*") => {
+                let len = code[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(code.len() - i, |end| end + 4);
+                out.extend_from_slice(&code[i..i + len]);
+                i += len;
+                continue;
+            }
+            b'u' | b'U' if i == 0 || !is_ident_char(code[i - 1]) => {
+                if let Some(len) = mangle_css_url_ref(rename, &code[i..], &mut out) {
+                    i += len;
+                    continue;
+                };
+            }
+            b'\\' => {
+                // Escapes outside of selectors can't be part of a name we've mangled, so copy them as is.
+                let len = if i + 1 < code.len() { 2 } else { 1 };
+                out.extend_from_slice(&code[i..i + len]);
+                i += len;
+                continue;
+            }
+            b'{' => {
+                blocks.push(in_declarations || prelude != Prelude::ConditionalAtRule);
+                prelude = Prelude::Selector;
+            }
+            b'}' => {
+                if blocks.len() > 1 {
+                    blocks.pop();
+                };
+                prelude = Prelude::Selector;
+            }
+            b';' => prelude = Prelude::Selector,
+            b'@' if !in_declarations => {
+                let name_len = code[i + 1..]
+                    .iter()
+                    .take_while(|&&c| is_ident_char(c))
+                    .count();
+                let name = code[i + 1..i + 1 + name_len].to_ascii_lowercase();
+                prelude = match name.as_slice() {
+                    b"media" | b"supports" | b"layer" | b"container" | b"document" => {
+                        Prelude::ConditionalAtRule
+                    }
+                    _ => Prelude::OtherAtRule,
+                };
+                out.extend_from_slice(&code[i..i + 1 + name_len]);
+                i += 1 + name_len;
+                continue;
+            }
+            b'.' | b'#' if !in_declarations && prelude == Prelude::Selector => {
+                let (len, name) = css_ident(&code[i + 1..]);
+                // `.5` is a number, not a class, in selectors like `:nth-child(.5)`.
+                if len > 0 && !(c == b'.' && code[i + 1].is_ascii_digit()) {
+                    // Mangled names never need escaping, but names that aren't mangled are written with their original
+                    // escapes.
+                    let kind = if c == b'.' {
+                        NameKind::Class
+                    } else {
+                        NameKind::Id
+                    };
+                    out.push(c);
+                    match rename(kind, &name) {
+                        Some(mangled) => out.extend_from_slice(&mangled),
+                        None => out.extend_from_slice(&code[i + 1..i + 1 + len]),
+                    };
+                    i += 1 + len;
+                    continue;
+                };
+            }
+            _ => {}
+        };
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn mangle_js_string_value(map: &NameMap, value: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mangled = match value.first() {
+        Some(b'.') => {
+            out.push(b'.');
+            lookup(&map.classes, &value[1..])?
+        }
+        Some(b'#') => {
+            out.push(b'#');
+            lookup(&map.ids, &value[1..])?
+        }
+        _ => lookup(&map.classes, value).or_else(|| lookup(&map.ids, value))?,
+    };
+    out.extend_from_slice(mangled);
+    Some(out)
+}
+
+// Renames string literals in JavaScript that are exactly a class name or ID, or a selector for one like `.name` or
+// `#name`. This is a heuristic, so it's only done when enabled.
+pub fn mangle_js_strings(map: &NameMap, code: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(code.len());
+    // The last character that isn't whitespace or part of a comment, which indicates whether a `/` starts a regex.
+    let mut last_significant = b';';
+    let mut i = 0;
+    while i < code.len() {
+        let c = code[i];
+        let len = match c {
+            b'"' | b'\'' => {
+                let len = string_len(&code[i..]);
+                let value = &code[i + 1..(i + len - 1).max(i + 1)];
+                match mangle_js_string_value(map, value).filter(|_| !value.contains(&b'\\')) {
+                    Some(mangled) => {
+                        out.push(c);
+                        out.extend_from_slice(&mangled);
+                        out.push(c);
+                        i += len;
+                        last_significant = c;
+                        continue;
+                    }
+                    None => len,
+                }
+            }
+            b'`' => string_len(&code[i..]),
+            b'/' if code[i..].starts_with(b"//") => code[i..]
+                .iter()
+                .position(|&c| c == b'\n')
+                .unwrap_or(code.len() - i),
+            b'/' if code[i..].starts_with(b"/*") => code[i + 2..]
+                .windows(2)
+                .position(|w| w == b"*/")
+                .map_or(code.len() - i, |end| end + 4),
+            b'/' if b"(,=:[!&|?{};+-*%<>~^".contains(&last_significant) => {
+                // A regex literal, which may contain quotes.
+                let mut j = i + 1;
+                let mut in_class = false;
+                while j < code.len() && code[j] != b'\n' {
+                    match code[j] {
+                        b'\\' => j += 1,
+                        b'[' => in_class = true,
+                        b']' => in_class = false,
+                        b'/' if !in_class => break,
+                        _ => {}
+                    };
+                    j += 1;
+                }
+                (j + 1).min(code.len()) - i
+            }
+            _ => 1,
+        };
+        out.extend_from_slice(&code[i..i + len]);
+        if !(WHITESPACE[c] || code[i..].starts_with(b"//") || code[i..].starts_with(b"/*")) {
+            last_significant = code[i + len - 1];
+        };
+        i += len;
+    }
+    out
+}
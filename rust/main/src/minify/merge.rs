@@ -0,0 +1,86 @@
+use crate::ast::{ElementClosingTag, NodeData};
+use minify_html_common::spec::tag::ns::Namespace;
+
+// Elements that render the same when adjacent ones are merged. Other formatting elements aren't, as each `a` is a
+// separate link, each `q` adds its own quotation marks, and ruby elements pair annotations with their base text.
+fn is_mergeable_tag(tag_name: &[u8]) -> bool {
+    matches!(
+        tag_name,
+        b"b" | b"i" | b"em" | b"strong" | b"span" | b"small" | b"s" | b"u" | b"sub" | b"sup"
+    )
+}
+
+fn can_merge(a: &NodeData, b: &NodeData) -> bool {
+    match (a, b) {
+        (
+            NodeData::Element {
+                attributes: a_attributes,
+                closing_tag: a_closing_tag,
+                name: a_name,
+                namespace: Namespace::Html,
+                ..
+            },
+            NodeData::Element {
+                attributes: b_attributes,
+                closing_tag: ElementClosingTag::Present | ElementClosingTag::Omitted,
+                name: b_name,
+                namespace: Namespace::Html,
+                ..
+            },
+        ) => {
+            *a_closing_tag == ElementClosingTag::Present
+                && a_name == b_name
+                && is_mergeable_tag(a_name)
+                && a_attributes == b_attributes
+        }
+        _ => false,
+    }
+}
+
+// Appends nodes to a list of sibling nodes, joining text nodes that become adjacent.
+pub fn append_nodes(nodes: &mut Vec<NodeData>, other: Vec<NodeData>) {
+    for n in other {
+        match (nodes.last_mut(), n) {
+            (Some(NodeData::Text { value: prev }), NodeData::Text { value }) => {
+                prev.extend_from_slice(&value)
+            }
+            (_, n) => nodes.push(n),
+        };
+    }
+}
+
+// Merges adjacent sibling formatting elements with the same tag name and attributes, like `<b>a</b><b>b</b>`, into a
+// single element, which renders the same. Their children are merged when their content is minified.
+pub fn merge_formatting_elements(nodes: &mut Vec<NodeData>) {
+    if !nodes.windows(2).any(|w| can_merge(&w[0], &w[1])) {
+        return;
+    };
+    let mut merged = Vec::<NodeData>::with_capacity(nodes.len());
+    for n in nodes.drain(..) {
+        match merged.last_mut() {
+            Some(prev) if can_merge(prev, &n) => {
+                if let (
+                    NodeData::Element {
+                        children: prev_children,
+                        closing_tag: prev_closing_tag,
+                        span: prev_span,
+                        ..
+                    },
+                    NodeData::Element {
+                        children,
+                        closing_tag,
+                        span,
+                        ..
+                    },
+                ) = (prev, n)
+                {
+                    append_nodes(prev_children, children);
+                    *prev_closing_tag = closing_tag;
+                    prev_span.end = span.end;
+                };
+            }
+            _ => merged.push(n),
+        };
+    }
+    *nodes = merged;
+}
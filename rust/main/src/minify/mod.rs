@@ -1,11 +1,23 @@
 pub mod attr;
 pub mod bang;
+pub mod class;
 pub mod comment;
 pub mod content;
+pub mod context;
 pub mod css;
 pub mod element;
+pub mod empty;
 pub mod esbuild;
+pub mod fragment;
 pub mod instruction;
 pub mod js;
+pub mod json;
+pub mod mangle;
+pub mod merge;
+pub mod script_html;
+pub mod sizes;
+pub mod srcset;
 #[cfg(test)]
 mod tests;
+pub mod url;
+pub mod whitespace_hints;
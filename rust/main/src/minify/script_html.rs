@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+
+use crate::cfg::Cfg;
+use crate::minify::context::MinifyContext;
+use crate::minify::fragment::minify_html_fragment;
+
+// Script types used by client-side templating libraries for `<script>` tags that contain HTML.
+pub fn default_html_script_types() -> HashSet<Vec<u8>> {
+    [
+        "text/html",
+        "text/ng-template",
+        "text/template",
+        "text/x-handlebars-template",
+        "text/x-template",
+    ]
+    .iter()
+    .map(|t| t.as_bytes().to_vec())
+    .collect()
+}
+
+// Minifies the content of a `<script>` tag as HTML. The content is left as is if the minified HTML contains `</script`,
+// which would end the `<script>` tag early. Closing tags are kept, as templates are usually inserted into an element of
+// the document rather than parsed as a separate document. `code_start` is the position of the content in the source.
+pub fn minify_html_script(
+    cfg: &Cfg,
+    ctx: &mut MinifyContext,
+    out: &mut Vec<u8>,
+    code: &[u8],
+    code_start: usize,
+) {
+    minify_html_fragment(cfg, ctx, out, false, code, code_start, b"</script");
+}
@@ -0,0 +1,36 @@
+use minify_html_common::gen::codepoints::WHITESPACE;
+
+// Minifies the value of a `sizes` attribute, which is a comma-separated list of CSS media conditions and lengths.
+// See https://html.spec.whatwg.org/multipage/images.html#sizes-attributes.
+// Whitespace is collapsed, and removed next to `(`, `)`, `,`, and `:`, which is where CSS doesn't require it.
+// Returns None if the value contains syntax we don't handle, in which case it should be left as is.
+pub fn minify_sizes(value: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(value.len());
+    let mut depth = 0usize;
+    let mut pending_space = false;
+    for &c in value {
+        if WHITESPACE[c] {
+            pending_space = !out.is_empty();
+            continue;
+        };
+        match c {
+            // Quotes, comments, and escapes can contain whitespace that must be preserved.
+            b'"' | b'\'' | b'\\' | b'/' => return None,
+            b'(' => depth += 1,
+            b')' => depth = depth.checked_sub(1)?,
+            _ => {}
+        };
+        if pending_space
+            && !matches!(c, b')' | b',' | b':')
+            && !matches!(out.last(), Some(b'(' | b',' | b':'))
+        {
+            out.push(b' ');
+        };
+        pending_space = false;
+        out.push(c);
+    }
+    if depth != 0 {
+        return None;
+    };
+    Some(out)
+}
@@ -0,0 +1,205 @@
+use std::str::from_utf8;
+
+use url::Url;
+
+use crate::minify::url::minify_url;
+use minify_html_common::gen::codepoints::{DIGIT, WHITESPACE};
+
+#[derive(PartialEq)]
+enum Descriptor {
+    Density(f64),
+    Width(u64),
+}
+
+struct Candidate {
+    url: Vec<u8>,
+    descriptor: Descriptor,
+    // The minified descriptors, which is empty if the candidate is equivalent to having no descriptors.
+    out: Vec<u8>,
+}
+
+// See https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-non-negative-integer.
+fn parse_integer(s: &[u8]) -> Option<u64> {
+    if s.is_empty() || !s.iter().all(|&c| DIGIT[c]) {
+        return None;
+    };
+    from_utf8(s).ok()?.parse().ok()
+}
+
+// See https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-floating-point-number.
+// Negative numbers aren't valid densities, so they aren't accepted.
+fn parse_float(s: &[u8]) -> Option<f64> {
+    let digits = |i: usize| s[i..].iter().take_while(|&&c| DIGIT[c]).count();
+    let mut i = digits(0);
+    let mut mantissa_digits = i;
+    if s.get(i) == Some(&b'.') {
+        let frac = digits(i + 1);
+        if frac == 0 {
+            return None;
+        };
+        mantissa_digits += frac;
+        i += 1 + frac;
+    };
+    if mantissa_digits == 0 {
+        return None;
+    };
+    if let Some(b'e' | b'E') = s.get(i) {
+        i += 1;
+        if let Some(b'+' | b'-') = s.get(i) {
+            i += 1;
+        };
+        let exp = digits(i);
+        if exp == 0 {
+            return None;
+        };
+        i += exp;
+    };
+    if i != s.len() {
+        return None;
+    };
+    from_utf8(s).ok()?.parse().ok()
+}
+
+// Returns the shortest representation of a floating-point number that parses to the same value.
+fn shortest_float(raw: &[u8], val: f64) -> Vec<u8> {
+    let formatted = format!("{}", val);
+    let formatted = formatted
+        .strip_prefix('0')
+        .filter(|f| f.starts_with('.'))
+        .unwrap_or(&formatted);
+    if formatted.len() < raw.len() {
+        formatted.as_bytes().to_vec()
+    } else {
+        raw.to_vec()
+    }
+}
+
+fn parse_candidate(url: Vec<u8>, descriptors: &[u8]) -> Option<Candidate> {
+    let mut density = None;
+    let mut width = None;
+    let mut height = None;
+    for d in descriptors
+        .split(|&c| WHITESPACE[c])
+        .filter(|d| !d.is_empty())
+    {
+        let (&unit, num) = d.split_last().unwrap();
+        match unit {
+            b'x' if density.is_none() => {
+                density = Some((num, parse_float(num)?));
+            }
+            b'w' if width.is_none() => {
+                width = Some(parse_integer(num).filter(|&w| w > 0)?);
+            }
+            b'h' if height.is_none() => {
+                height = Some(parse_integer(num).filter(|&h| h > 0)?);
+            }
+            _ => return None,
+        };
+    }
+    let mut out = Vec::new();
+    let descriptor = match (density, width) {
+        (Some(_), Some(_)) => return None,
+        // A height descriptor is only valid with a width descriptor.
+        (_, None) if height.is_some() => return None,
+        (Some((raw, d)), None) => {
+            if d != 1.0 {
+                out.extend_from_slice(&shortest_float(raw, d));
+                out.push(b'x');
+            };
+            Descriptor::Density(d)
+        }
+        (None, Some(w)) => {
+            out.extend_from_slice(format!("{}w", w).as_bytes());
+            if let Some(h) = height {
+                out.extend_from_slice(format!(" {}h", h).as_bytes());
+            };
+            Descriptor::Width(w)
+        }
+        (None, None) => Descriptor::Density(1.0),
+    };
+    Some(Candidate {
+        url,
+        descriptor,
+        out,
+    })
+}
+
+// Minifies the value of a `srcset` attribute, as parsed by
+// https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute.
+// Returns None if the value is malformed, in which case it should be left as is.
+pub fn minify_srcset(base_url: Option<&Url>, value: &[u8]) -> Option<Vec<u8>> {
+    let mut candidates = Vec::<Candidate>::new();
+    let mut i = 0;
+    loop {
+        while value
+            .get(i)
+            .filter(|&&c| WHITESPACE[c] || c == b',')
+            .is_some()
+        {
+            i += 1;
+        }
+        if i >= value.len() {
+            break;
+        };
+        let url_len = value[i..].iter().take_while(|&&c| !WHITESPACE[c]).count();
+        let mut url = value[i..i + url_len].to_vec();
+        i += url_len;
+        let descriptors = if url.ends_with(b",") {
+            while url.last() == Some(&b',') {
+                url.pop();
+            }
+            &value[i..i]
+        } else {
+            let len = value[i..].iter().take_while(|&&c| c != b',').count();
+            let descriptors = &value[i..i + len];
+            // We don't handle descriptors containing parentheses, which are reserved for future use.
+            if descriptors.contains(&b'(') {
+                return None;
+            };
+            i += len;
+            descriptors
+        };
+        let mut candidate = parse_candidate(url, descriptors)?;
+        if let Some(base_url) = base_url {
+            let min = minify_url(base_url, &candidate.url);
+            // A URL starting or ending with a comma would not be parsed back correctly.
+            if !min.starts_with(b",") && !min.ends_with(b",") {
+                candidate.url = min;
+            };
+        };
+        // Later candidates with the same descriptor are ignored.
+        if candidates
+            .iter()
+            .all(|c| c.descriptor != candidate.descriptor)
+        {
+            candidates.push(candidate);
+        };
+    }
+    if candidates.is_empty() {
+        return None;
+    };
+    // We can't mix width and density descriptors, as candidates without descriptors are treated as 1x.
+    let widths = candidates
+        .iter()
+        .filter(|c| matches!(c.descriptor, Descriptor::Width(_)))
+        .count();
+    if widths != 0 && widths != candidates.len() {
+        return None;
+    };
+
+    // A URL without descriptors must be last, as otherwise a following comma and URL would be parsed as part of it.
+    // The order of candidates doesn't matter as their descriptors are unique.
+    candidates.sort_by_key(|c| c.out.is_empty());
+    let mut out = Vec::with_capacity(value.len());
+    for (i, c) in candidates.into_iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        };
+        out.extend_from_slice(&c.url);
+        if !c.out.is_empty() {
+            out.push(b' ');
+            out.extend_from_slice(&c.out);
+        };
+    }
+    Some(out)
+}
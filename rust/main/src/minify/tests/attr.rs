@@ -1,3 +1,7 @@
+use minify_html_common::spec::attr::RedundantAttributeCategory;
+
+use crate::cfg::Cfg;
+use crate::minify;
 use crate::minify::attr::{
     encode_unquoted, encode_using_double_quotes, encode_using_single_quotes,
 };
@@ -28,3 +32,57 @@ fn test_encode_unquoted() {
         r#"&#34;123'&#32'h&#32&#32&#32;0&#32&amp&amp;&#32;;abbibi&#32"'&#32\&#32&GT&&#32;3&GT;;"#,
     );
 }
+
+#[test]
+fn test_attr_whatwg_unquoted_value_quoting() {
+    let mut cfg = Cfg::new();
+    cfg.ensure_spec_compliant_unquoted_attribute_values = true;
+    assert_eq!(minify(b"<a b==></a>", &cfg), br#"<a b="="></a>"#.to_vec());
+}
+
+#[test]
+fn test_redundant_attributes() {
+    let eval = |src: &[u8], expected: &[u8], keep: &[RedundantAttributeCategory]| {
+        let mut cfg = Cfg::new();
+        cfg.keep_redundant_attributes.extend(keep.iter().copied());
+        assert_eq!(
+            String::from_utf8(minify(src, &cfg)).unwrap(),
+            String::from_utf8(expected.to_vec()).unwrap(),
+        );
+    };
+    eval(b"<form method=GET></form>", b"<form></form>", &[]);
+    eval(
+        b"<form method=post></form>",
+        b"<form method=post></form>",
+        &[],
+    );
+    eval(
+        b"<style type=\" TEXT/CSS \"></style><link rel=\"preload stylesheet\" type=text/css href=a>",
+        b"<style></style><link href=a rel=\"preload stylesheet\">",
+        &[],
+    );
+    eval(
+        b"<script charset=utf-8 src=a></script><script charset=latin1 src=b></script><script charset=latin1></script>",
+        b"<script src=a></script><script charset=latin1 src=b></script><script></script>",
+        &[],
+    );
+    eval(
+        b"<script language=JavaScript></script><script language=vbscript></script><script language=vbscript type=module></script>",
+        b"<script></script><script language=vbscript></script><script type=module></script>",
+        &[],
+    );
+    // The type can't be removed if there's a language, as it would then take effect.
+    eval(
+        b"<script language=vbscript type=text/javascript></script><script type=text/javascript></script>",
+        b"<script type=text/javascript></script><script></script>",
+        &[],
+    );
+    eval(
+        b"<form method=GET></form><script type=text/javascript language=javascript></script>",
+        b"<form method=GET></form><script type=text/javascript></script>",
+        &[
+            RedundantAttributeCategory::FormMethod,
+            RedundantAttributeCategory::ScriptType,
+        ],
+    );
+}
@@ -0,0 +1,122 @@
+use crate::cache::MinifyCache;
+use crate::{CssOptions, JsOptions};
+
+#[test]
+fn test_cache_in_memory() {
+    let cache = MinifyCache::in_memory();
+    let key = MinifyCache::key("js", &JsOptions::default(), b"let a = 1;");
+    assert_eq!(cache.get(&key), None);
+    cache.insert(key.clone(), b"let a=1;".to_vec());
+    assert_eq!(cache.get(&key), Some(b"let a=1;".to_vec()));
+    assert_eq!(
+        cache.get(&MinifyCache::key(
+            "js-module",
+            &JsOptions::default(),
+            b"let a = 1;"
+        )),
+        None
+    );
+}
+
+#[test]
+fn test_cache_key_includes_versions() {
+    let key = MinifyCache::key("js", &JsOptions::default(), b"let a = 1;");
+    let prefix = format!("{} 0.12.19 js ", env!("CARGO_PKG_VERSION"));
+    assert!(key.starts_with(prefix.as_bytes()));
+}
+
+#[test]
+fn test_cache_in_memory_evicts_oldest_entries() {
+    let cache = MinifyCache::in_memory();
+    let keys = (0..65)
+        .map(|i| MinifyCache::key("js", &JsOptions::default(), format!("{}", i).as_bytes()))
+        .collect::<Vec<_>>();
+    for k in keys.iter() {
+        cache.insert(k.clone(), vec![b'a'; 1024 * 1024]);
+    }
+    assert_eq!(cache.get(&keys[0]), None);
+    assert!(cache.get(&keys[1]).is_some());
+    assert!(cache.get(&keys[64]).is_some());
+}
+
+#[test]
+fn test_cache_on_disk() {
+    let dir = std::env::temp_dir().join(format!("minify-html-cache-test-{}", std::process::id()));
+    let key = MinifyCache::key("css", &CssOptions::default(), b"a { color: red; }");
+    MinifyCache::on_disk(&dir).insert(key.clone(), b"a{color:red}".to_vec());
+    // A new cache reads the entries saved by another.
+    let cache = MinifyCache::on_disk(&dir);
+    assert_eq!(cache.get(&key), Some(b"a{color:red}".to_vec()));
+    assert_eq!(cache.get(&key[1..]), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_minify_with_cache() {
+    use crate::{minify, Cfg};
+    use std::sync::Arc;
+
+    let cache = Arc::new(MinifyCache::in_memory());
+    let mut cfg = Cfg::new();
+    cfg.minify_js = true;
+    cfg.cache = Some(cache.clone());
+    let key = MinifyCache::key("js", &(&cfg.js_options, cfg.ascii_only), b"let a = 1;");
+    cache.insert(key, b"let b=2;".to_vec());
+    // The cached result is used instead of minifying the code.
+    assert_eq!(
+        minify(b"<script>let a = 1;</script>", &cfg),
+        b"<script>let b=2;</script>".to_vec()
+    );
+    assert_eq!(
+        minify(b"<script>let c = 3;</script>", &cfg),
+        b"<script>let c=3;</script>".to_vec()
+    );
+    assert_eq!(
+        cache.get(&MinifyCache::key(
+            "js",
+            &(&cfg.js_options, cfg.ascii_only),
+            b"let c = 3;"
+        )),
+        Some(b"let c=3;".to_vec())
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_minify_attributes_with_cache() {
+    use crate::{minify, Cfg};
+    use std::sync::Arc;
+
+    let cache = Arc::new(MinifyCache::in_memory());
+    let mut cfg = Cfg::new();
+    cfg.minify_css = true;
+    cfg.minify_js = true;
+    cfg.cache = Some(cache.clone());
+    let js_options = (&cfg.js_options, cfg.ascii_only);
+    let css_options = (&cfg.css_options, cfg.ascii_only);
+    cache.insert(
+        MinifyCache::key("js-function-body", &js_options, b"f( 1 )"),
+        b"g()".to_vec(),
+    );
+    cache.insert(
+        MinifyCache::key("css-declarations", &css_options, b"color: red"),
+        b"color:blue".to_vec(),
+    );
+    assert_eq!(
+        minify(b"<p onclick=\"f( 1 )\" style=\"color: red\">", &cfg),
+        b"<p onclick=g() style=color:blue>".to_vec()
+    );
+    assert_eq!(
+        minify(b"<p onclick=\"f( 2 )\">", &cfg),
+        b"<p onclick=f(2)>".to_vec()
+    );
+    assert_eq!(
+        cache.get(&MinifyCache::key(
+            "js-function-body",
+            &js_options,
+            b"f( 2 )"
+        )),
+        Some(b"f(2)".to_vec())
+    );
+}
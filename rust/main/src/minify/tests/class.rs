@@ -0,0 +1,35 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8], sort_class_names: bool) {
+    let mut cfg = Cfg::new();
+    cfg.sort_class_names = sort_class_names;
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_minify_class() {
+    eval(
+        b"<div class=\" btn\n btn  primary btn \"></div>",
+        b"<div class=\"btn primary\"></div>",
+        false,
+    );
+    eval(b"<div class=\"  \"></div>", b"<div></div>", false);
+    eval(
+        b"<svg class=\"a  b a\"></svg>",
+        b"<svg class=\"a b\"></svg>",
+        false,
+    );
+}
+
+#[test]
+fn test_sort_class_names() {
+    eval(
+        b"<p class=\"x btn\">1<p class=\"primary btn\">2<p class=\"btn primary z\">3",
+        b"<p class=\"btn x\">1<p class=\"btn primary\">2<p class=\"btn primary z\">3",
+        true,
+    );
+}
@@ -0,0 +1,64 @@
+use crate::cfg::Cfg;
+use crate::comment_rule::CommentRule;
+use crate::minify;
+
+fn eval_with_cfg(src: &[u8], expected: &[u8], cfg: &Cfg) {
+    assert_eq!(
+        String::from_utf8(minify(src, cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+fn eval(src: &[u8], expected: &[u8]) {
+    eval_with_cfg(src, expected, &Cfg::new());
+}
+
+#[test]
+fn test_unclosed_conditional_comment() {
+    eval(b"<!--[if IE]><p>unclosed", b"");
+}
+
+#[test]
+fn test_downlevel_revealed_conditional_comments() {
+    eval(
+        b"<!--[if !IE]><!--> <p>Not IE</p> <!--<![endif]-->",
+        b"<!--[if !IE]><!--><p>Not IE<!--<![endif]-->",
+    );
+    let mut cfg = Cfg::new();
+    cfg.remove_bangs = true;
+    eval_with_cfg(
+        b"<![if !IE]><p>Not IE</p><![endif]><!DOCTYPE>",
+        b"<![if !IE]><p>Not IE<![endif]>",
+        &cfg,
+    );
+}
+
+#[test]
+fn test_keep_comments_matching() {
+    let mut cfg = Cfg::new();
+    cfg.keep_comments_matching = vec![
+        CommentRule::Prefix(b"!".to_vec()),
+        CommentRule::regex(r"^\s*/?ko(\s|$)").unwrap(),
+        CommentRule::regex("^$").unwrap(),
+    ];
+    eval_with_cfg(
+        b"<!--! Copyright --><!-- comment --><ul><!-- ko foreach: items --><li>a<!-- /ko --><!-- kode --></ul><!---->",
+        b"<!--! Copyright --><ul><!-- ko foreach: items --><li>a<!-- /ko --></ul><!---->",
+        &cfg,
+    );
+    cfg.remove_empty_elements = true;
+    eval_with_cfg(
+        b"<div><!----></div><div><!-- x --></div>",
+        b"<div><!----></div>",
+        &cfg,
+    );
+}
+
+#[test]
+fn test_keep_server_side_includes() {
+    let src = b"<!--#include virtual=\"/header.html\" --><!--#--><!-- #echo -->";
+    eval(src, b"");
+    let mut cfg = Cfg::new();
+    cfg.keep_server_side_includes = true;
+    eval_with_cfg(src, b"<!--#include virtual=\"/header.html\" -->", &cfg);
+}
@@ -0,0 +1,51 @@
+use minify_html_common::spec::tag::whitespace::WhitespaceMinification;
+
+use crate::cfg::Cfg;
+use crate::minify;
+
+#[test]
+fn test_whitespace_minification_overrides() {
+    let src = b"<div> <x-button> <b>Save</b> changes </x-button> <ui-stack> <p>a</p> <p>b</p> </ui-stack> </div>";
+    let mut cfg = Cfg::new();
+    assert_eq!(
+        minify(src, &cfg),
+        b"<div><x-button> <b>Save</b> changes </x-button><ui-stack> <p>a</p> <p>b</p> </ui-stack></div>".to_vec()
+    );
+    cfg.whitespace_minification_overrides.insert(
+        b"ui-stack".to_vec(),
+        WhitespaceMinification {
+            collapse: true,
+            destroy_whole: true,
+            trim: true,
+        },
+    );
+    cfg.whitespace_minification_overrides.insert(
+        b"div".to_vec(),
+        WhitespaceMinification {
+            collapse: true,
+            destroy_whole: false,
+            trim: false,
+        },
+    );
+    assert_eq!(
+        minify(src, &cfg),
+        b"<div> <x-button> <b>Save</b> changes </x-button> <ui-stack><p>a<p>b</ui-stack> </div>"
+            .to_vec()
+    );
+}
+
+#[test]
+fn test_conservative_whitespace() {
+    let src =
+        b"<div>\n  <p> Hello,\n   <b>world</b> ! </p>\n  <span>a</span>  <span>b</span>\n</div>";
+    let mut cfg = Cfg::new();
+    assert_eq!(
+        minify(src, &cfg),
+        b"<div><p>Hello, <b>world</b> !</p><span>a</span><span>b</span></div>".to_vec()
+    );
+    cfg.conservative_whitespace = true;
+    assert_eq!(
+        minify(src, &cfg),
+        b"<div><p> Hello,\n<b>world</b> ! </p>\n<span>a</span> <span>b</span></div>".to_vec()
+    );
+}
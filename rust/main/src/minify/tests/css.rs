@@ -0,0 +1,40 @@
+use crate::cfg::Cfg;
+use crate::minify::css::write_css;
+
+#[test]
+fn test_write_css_escapes_non_ascii_characters_if_ascii_only() {
+    let mut cfg = Cfg::new();
+    cfg.ascii_only = true;
+    let mut out = Vec::new();
+    write_css(
+        &cfg,
+        &mut out,
+        "a::after{content:'\u{E9}1\u{E9}x\u{E9} \\\u{E9}'}".as_bytes(),
+    );
+    assert_eq!(
+        std::str::from_utf8(&out).unwrap(),
+        r"a::after{content:'\e9 1\e9x\e9  \e9'}"
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_is_well_formed_declaration_list() {
+    use crate::minify::css::is_well_formed_declaration_list;
+
+    assert!(is_well_formed_declaration_list(b""));
+    assert!(is_well_formed_declaration_list(
+        br#"color: red; background: url("a}.png") /* } */; content: '\'{'"#
+    ));
+    assert!(is_well_formed_declaration_list(
+        b"width: calc((1px + 2px) * [3])"
+    ));
+    assert!(!is_well_formed_declaration_list(b"a}/*"));
+    assert!(!is_well_formed_declaration_list(
+        b"color: red} a{color: blue"
+    ));
+    assert!(!is_well_formed_declaration_list(b"content: 'abc"));
+    assert!(!is_well_formed_declaration_list(b"width: calc((1px)"));
+    assert!(!is_well_formed_declaration_list(b"width: calc(1px]"));
+    assert!(!is_well_formed_declaration_list(b"content: a\\"));
+}
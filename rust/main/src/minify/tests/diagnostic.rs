@@ -0,0 +1,48 @@
+use crate::cfg::Cfg;
+use crate::{minify_with_diagnostics, DiagnosticType};
+
+#[test]
+fn test_diagnostic_spans() {
+    let cfg = Cfg::new();
+    for (src, span) in [
+        (
+            b"<p>a</p><script type=application/json>{ a: 1 }</script>".as_ref(),
+            8..55,
+        ),
+        // Spans in nested HTML are positions in the document.
+        (b"<!--esi <script type=importmap>{ a }</script>-->", 8..45),
+        (
+            b"<!--[if IE]><script type=importmap>[1,]</script><![endif]-->",
+            12..48,
+        ),
+    ] {
+        let (_, diagnostics) = minify_with_diagnostics(src, &cfg);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].diagnostic_type,
+            DiagnosticType::MalformedJson
+        );
+        assert_eq!(diagnostics[0].span, span);
+        assert!(src[span].starts_with(b"<script"));
+    }
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_esbuild_errors() {
+    let mut cfg = Cfg::new();
+    cfg.minify_js = true;
+    let src = b"<p><script>let a = 1;\nlet b = ;</script><script>let c = 1;</script>";
+    let (out, diagnostics) = minify_with_diagnostics(src, &cfg);
+    assert_eq!(
+        out,
+        b"<p><script>let a = 1;\nlet b = ;</script><script>let c=1;</script>".to_vec()
+    );
+    assert_eq!(diagnostics.len(), 1);
+    match &diagnostics[0].diagnostic_type {
+        DiagnosticType::ScriptMinificationFailed(errors) => assert_eq!(errors[0].line, 2),
+        _ => unreachable!(),
+    };
+    assert_eq!(diagnostics[0].code, b"let a = 1;\nlet b = ;".to_vec());
+    assert_eq!(diagnostics[0].span, 3..40);
+}
@@ -0,0 +1,87 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8]) {
+    let cfg = Cfg::new();
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+fn eval_with_keep_html_head(src: &[u8], expected: &[u8]) {
+    let mut cfg = Cfg::new();
+    cfg.keep_html_and_head_opening_tags = true;
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_body_opening_tag_omission() {
+    eval(
+        b"<html><head><title>a</title></head><body><p>b</p></body></html>",
+        b"<title>a</title><p>b",
+    );
+    eval(b"<html><body></body></html>", b"");
+    eval(
+        b"<head><title>a</title></head><body>  <script>b</script></body>",
+        b"<title>a</title><body><script>b</script>",
+    );
+    // Conditions are checked against the minified content, which has no comments.
+    eval(b"<body><!-- a --><p>b</p></body>", b"<p>b");
+    eval(b"<body class=\"a\"><p>b</p></body>", b"<body class=a><p>b");
+}
+
+#[test]
+fn test_colgroup_and_tbody_opening_tag_omission() {
+    eval(
+        b"<table><colgroup><col></colgroup><tbody><tr><td>a</td></tr></tbody></table>",
+        b"<table><col></colgroup><tr><td>a</table>",
+    );
+    eval(
+        b"<table><colgroup><col></colgroup><colgroup><col></colgroup></table>",
+        b"<table><col></colgroup><col></colgroup></table>",
+    );
+    eval(
+        b"<table><thead><tr><td>a</td></tr></thead><tbody><tr><td>b</td></tr></tbody></table>",
+        b"<table><thead><tr><td>a<tbody><tr><td>b</table>",
+    );
+    eval(
+        b"<table><tbody><tr><td>a</td></tr></tbody><tbody><tr><td>b</td></tr></tbody></table>",
+        b"<table><tr><td>a<tbody><tr><td>b</table>",
+    );
+}
+
+#[test]
+fn test_parsing_extra_head_tag() {
+    // Extra `<head>` in `<label>` should be dropped, so whitespace around `<head>` should be joined and therefore trimmed due to `<label>` whitespace rules.
+    eval_with_keep_html_head(
+        b"<html><head><meta><head><link><head><body><label>  <pre> </pre> <head>  </label>",
+        b"<html><head><meta><link><label><pre> </pre></label>",
+    );
+    // Same as above except it's a `</head>`, which should get reinterpreted as a `<head>`.
+    eval_with_keep_html_head(
+        b"<html><head><meta><head><link><head><body><label>  <pre> </pre> </head>  </label>",
+        b"<html><head><meta><link><label><pre> </pre></label>",
+    );
+    // `<head>` gets implicitly closed by `<body>`, so any following `</head>` should be ignored. (They should be anyway, since `</head>` would not be a valid closing tag.)
+    eval_with_keep_html_head(
+        b"<html><head><body><label> </head> </label>",
+        b"<html><head><label></label>",
+    );
+}
+
+#[test]
+fn test_unmatched_closing_tag() {
+    eval_with_keep_html_head(b"Hello</p>Goodbye", b"Hello<p>Goodbye");
+    eval_with_keep_html_head(b"Hello<br></br>Goodbye", b"Hello<br>Goodbye");
+    eval_with_keep_html_head(b"<div>Hello</p>Goodbye", b"<div>Hello<p>Goodbye");
+    eval_with_keep_html_head(b"<ul><li>a</p>", b"<ul><li>a<p>");
+    eval_with_keep_html_head(b"<ul><li><rt>a</p>", b"<ul><li><rt>a<p>");
+    eval_with_keep_html_head(
+        b"<html><head><body><ul><li><rt>a</p>",
+        b"<html><head><ul><li><rt>a<p>",
+    );
+}
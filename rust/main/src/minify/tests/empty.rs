@@ -0,0 +1,26 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8]) {
+    let mut cfg = Cfg::new();
+    cfg.remove_empty_elements = true;
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_remove_empty_elements() {
+    eval(
+        b"<div><p></p><div> <!-- a --> </div><p>a</p><p> <span></span> </p></div>",
+        b"<div><p>a</div>",
+    );
+    eval(b"<p>a <b></b> b</p>", b"<p>a b");
+    // Whitespace in formatting elements could be rendered.
+    eval(b"<p>a<span> </span>b</p>", b"<p>a<span> </span>b");
+    eval(
+        b"<div class=a></div><td></td><x-icon></x-icon><textarea></textarea>",
+        b"<div class=a></div><td></td><x-icon></x-icon><textarea></textarea>",
+    );
+}
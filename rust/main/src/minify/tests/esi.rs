@@ -0,0 +1,56 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8]) {
+    assert_eq!(
+        String::from_utf8(minify(src, &Cfg::new())).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_esi_elements() {
+    eval(
+        b"<div><esi:include src=\"/header.html\" /> <p>a</p></div>",
+        b"<div><esi:include src=/header.html /><p>a</div>",
+    );
+    eval(
+        b"<p>a<esi:include src=\"/b\"/>c</p><p>d</p>",
+        b"<p>a<esi:include src=/b />c<p>d",
+    );
+    eval(
+        b"<esi:remove> <a href=\"/fallback\">Fallback</a> </esi:remove>",
+        b"<esi:remove> <a href=/fallback>Fallback</a> </esi:remove>",
+    );
+    eval(
+        b"<esi:remove><div>a</esi:remove><p>b</p>",
+        b"<esi:remove><div>a</esi:remove><p>b",
+    );
+    eval(b"<div><p>a</esi:remove>b</p></div>c", b"<div><p>ab</div>c");
+    eval(
+        b"<table><tr><td>a</esi:remove>b</td></tr></table>",
+        b"<table><tr><td>ab</table>",
+    );
+    eval(
+        b"<esi:remove><ul><li><p>a</esi:remove>b",
+        b"<esi:remove><ul><li><p>a</esi:remove>b",
+    );
+}
+
+#[test]
+fn test_esi_comments() {
+    eval(
+        b"<div><!--esi  <p> a </p>  <esi:include src=\"/b\"/> --></div>",
+        b"<div><!--esi <p>a</p><esi:include src=/b />--></div>",
+    );
+    eval(
+        b"<ul><li>a</li><!--esi <li>b</li> --></ul>",
+        b"<ul><li>a</li><!--esi <li>b</li>--></ul>",
+    );
+    eval(b"<!--esimate--><!--esi", b"");
+    // The HTML is left as is if minifying it would end the comment early.
+    eval(
+        b"<!--esi  <p>a--&gt;b</p> --><p>x",
+        b"<!--esi  <p>a--&gt;b</p> --><p>x",
+    );
+}
@@ -0,0 +1,112 @@
+use crate::cfg::Cfg;
+use crate::minify;
+use crate::minify::js::write_js;
+
+#[test]
+fn test_write_js_escapes_non_ascii_characters_if_ascii_only() {
+    let mut cfg = Cfg::new();
+    cfg.ascii_only = true;
+    let mut out = Vec::new();
+    write_js(
+        &cfg,
+        &mut out,
+        "let caf\u{E9} = '\\\u{E9}\u{1F600}\\\\\u{E9}';".as_bytes(),
+    );
+    assert_eq!(
+        std::str::from_utf8(&out).unwrap(),
+        r"let caf\u00e9 = '\u00e9\ud83d\ude00\\\u00e9';"
+    );
+}
+
+#[test]
+fn test_module_scripts() {
+    let cfg = Cfg::new();
+    assert_eq!(
+        minify(
+            b"<script type=\"module\">import a from './a.js';\nawait a();</script><script nomodule=\"nomodule\" src=\"a.js\"></script>",
+            &cfg,
+        ),
+        b"<script type=module>import a from './a.js';\nawait a();</script><script nomodule src=a.js></script>".to_vec(),
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_js_options() {
+    let src = "<script>/*! license */\nlet caf\u{E9} = '\u{E9}';\nconsole.log(caf\u{E9});</script>"
+        .as_bytes();
+    let mut cfg = Cfg::new();
+    cfg.minify_js = true;
+
+    let out = minify(src, &cfg);
+    assert!(!out.windows(14).any(|w| w == b"/*! license */"));
+    assert!(!out.is_ascii());
+
+    cfg.ascii_only = true;
+    cfg.js_options.keep_legal_comments = true;
+    let out = minify(src, &cfg);
+    assert!(out.windows(14).any(|w| w == b"/*! license */"));
+    assert!(out.is_ascii());
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_parallel_minification() {
+    let mut cfg = Cfg::new();
+    cfg.esbuild_threads = 2;
+    cfg.minify_css = true;
+    cfg.minify_js = true;
+    assert_eq!(
+        minify(
+            b"<head><script>let a = 1;</script><style>a { color: red; }</style><script>let b = 2;</script></head><p><script>let c = 3;</script>",
+            &cfg,
+        ),
+        b"<script>let a=1;</script><style>a{color:red}</style><script>let b=2;</script><p><script>let c=3;</script>".to_vec(),
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_event_handler_minification() {
+    let mut cfg = Cfg::new();
+    cfg.minify_js = true;
+    assert_eq!(
+        minify(b"<button onclick=\"alert( 1 );\"></button>", &cfg),
+        b"<button onclick=alert(1)></button>".to_vec(),
+    );
+    // Code that esbuild can't parse is left as is.
+    assert_eq!(
+        minify(b"<button onclick=\"alert( 1\"></button>", &cfg),
+        b"<button onclick=\"alert( 1\"></button>".to_vec(),
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_js_url_minification() {
+    let mut cfg = Cfg::new();
+    cfg.minify_js = true;
+    // Statements aren't rewritten, as that could change the completion value.
+    assert_eq!(
+        minify(b"<a href=\"javascript: if ( a ) '<p>'\"></a>", &cfg),
+        b"<a href='javascript:if(a)\"<p>\";'></a>".to_vec(),
+    );
+    assert_eq!(
+        minify(b"<iframe src=\"JavaScript:void ( 0 )\"></iframe>", &cfg),
+        b"<iframe src=\"JavaScript:void 0;\"></iframe>".to_vec(),
+    );
+    // Code that esbuild can't parse is left as is.
+    assert_eq!(
+        minify(b"<a href=\"javascript:alert( 1\"></a>", &cfg),
+        b"<a href=\"javascript:alert( 1\"></a>".to_vec(),
+    );
+    // Code that is percent-decoded or has newlines removed before it's run is left as is.
+    assert_eq!(
+        minify(b"<a href=\"javascript:alert( '%41' )\"></a>", &cfg),
+        b"<a href=\"javascript:alert( '%41' )\"></a>".to_vec(),
+    );
+    assert_eq!(
+        minify(b"<a href=\"javascript:a = 1\nb = 2\"></a>", &cfg),
+        b"<a href=\"javascript:a = 1\nb = 2\"></a>".to_vec(),
+    );
+}
@@ -0,0 +1,54 @@
+use crate::cfg::Cfg;
+use crate::minify::json::minify_json;
+use crate::{minify, minify_with_diagnostics, DiagnosticType};
+
+fn eval_json(src: &[u8], expected: Option<&[u8]>) {
+    assert_eq!(
+        minify_json(src).map(|m| String::from_utf8(m).unwrap()),
+        expected.map(|e| String::from_utf8(e.to_vec()).unwrap()),
+    );
+}
+
+#[test]
+fn test_minify_json() {
+    eval_json(
+        b" {\n  \"a\" : [ 1 , -2.5e+3 , true , false , null ] ,\r\n\t\"b c\" : { } , \"d\" : [ ] }\n",
+        Some(b"{\"a\":[1,-2.5e+3,true,false,null],\"b c\":{},\"d\":[]}"),
+    );
+    eval_json(b"\"\\u00e9\\n\\\"\"", Some(b"\"\\u00e9\\n\\\"\""));
+    eval_json(
+        b"[\"<!-- a --> <\\/script> </SCRIPT\"]",
+        Some(b"[\"\\u003C!-- a --> <\\/script> <\\/SCRIPT\"]"),
+    );
+    eval_json(b"", None);
+    eval_json(b"{\"a\":1,}", None);
+    eval_json(b"[01]", None);
+    eval_json(b"{'a':1}", None);
+    eval_json(b"[1] [2]", None);
+    eval_json(b"\"a\nb\"", None);
+    eval_json(b"\"\\x\"", None);
+    eval_json(&[b'['; 1000], None);
+}
+
+#[test]
+fn test_json_scripts() {
+    let cfg = Cfg::new();
+    assert_eq!(
+        minify(
+            b"<script type=\"application/ld+json\">\n  { \"@context\": \"https://schema.org\" }\n</script><script type=importmap>{ \"imports\": {} }</script>",
+            &cfg,
+        ),
+        b"<script type=application/ld+json>{\"@context\":\"https://schema.org\"}</script><script type=importmap>{\"imports\":{}}</script>".to_vec(),
+    );
+    let (out, diagnostics) =
+        minify_with_diagnostics(b"<script type=application/json>{ a: 1 }</script>", &cfg);
+    assert_eq!(
+        out,
+        b"<script type=application/json>{ a: 1 }</script>".to_vec()
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].diagnostic_type,
+        DiagnosticType::MalformedJson
+    );
+}
@@ -0,0 +1,106 @@
+use crate::cfg::Cfg;
+use crate::minify::mangle::{mangle_css, mangle_js_strings};
+use crate::minify_with_name_map;
+use crate::name_map::NameMap;
+
+fn name_map(classes: &[(&str, &str)], ids: &[(&str, &str)]) -> NameMap {
+    let to_map = |names: &[(&str, &str)]| {
+        names
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    };
+    NameMap {
+        classes: to_map(classes),
+        ids: to_map(ids),
+    }
+}
+
+#[test]
+fn test_minify_with_name_map() {
+    let mut map = name_map(&[("existing", "a")], &[]);
+    let (out, _) = minify_with_name_map(
+        b"<style>.btn.primary, #main > .existing { color: #fff }</style><div id=main><button class=\"btn primary btn\">1</button><button class=\"btn existing\" aria-controls=\"main other\">2</button><a href=#main>3</a></div>",
+        &Cfg::new(),
+        &mut map,
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "<style>.b.c, #a > .a { color: #fff }</style><div id=a><button class=\"b c\">1</button><button aria-controls=\"a other\"class=\"b a\">2</button><a href=#a>3</a></div>",
+    );
+    assert_eq!(
+        map,
+        name_map(
+            &[("existing", "a"), ("btn", "b"), ("primary", "c")],
+            &[("main", "a")]
+        )
+    );
+}
+
+#[test]
+fn test_mangle_css() {
+    let map = name_map(&[("x", "a")], &[("y", "b")]);
+    assert_eq!(
+        String::from_utf8(mangle_css(
+            &map,
+            b"@media (min-width: 1.5em) { .x, a[href='.x'] { color: #y } } @font-face { src: url(a.x) } #y:nth-child(.5) /* .x */ { }"
+        ))
+        .unwrap(),
+        "@media (min-width: 1.5em) { .a, a[href='.x'] { color: #y } } @font-face { src: url(a.x) } #b:nth-child(.5) /* .x */ { }",
+    );
+}
+
+#[test]
+fn test_mangle_css_escapes_and_url_refs() {
+    let map = name_map(&[("md:flex", "a"), ("1x", "b")], &[("grad", "c")]);
+    assert_eq!(
+        String::from_utf8(mangle_css(
+            &map,
+            b".md\\:flex, .\\31 x, .\\31x, .other\\:x { fill: url(#grad); stroke: URL( '#grad' ) } #grad { mask: url(#none) }"
+        ))
+        .unwrap(),
+        ".a, .b, .b, .other\\:x { fill: url(#c); stroke: URL( '#c' ) } #c { mask: url(#none) }",
+    );
+}
+
+#[test]
+fn test_minify_with_name_map_url_refs() {
+    let mut map = NameMap::default();
+    let (out, _) = minify_with_name_map(
+        b"<style>.md\\:flex { color: red }</style><div class=\"md:flex\" style=\"mask: url(#grad)\"></div><svg><g id=grad /><rect fill=\"url(#grad)\" data-x=\"url(#grad)\" /></svg>",
+        &Cfg::new(),
+        &mut map,
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "<style>.a { color: red }</style><div class=a style=\"mask: url(#a)\"></div><svg><g id=a /><rect data-x=url(#grad) fill=url(#a) /></svg>",
+    );
+}
+
+#[test]
+fn test_minify_with_name_map_skips_unrenamed_names() {
+    let mut map = name_map(&[], &[]);
+    let (out, _) = minify_with_name_map(
+        b"<style>.a{color:red}.message{color:blue}#a{}</style><p class=message id=main style=\"--x:url(#b)\">x</p><label for=c>y</label>",
+        &Cfg::new(),
+        &mut map,
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "<style>.a{color:red}.b{color:blue}#a{}</style><p class=b id=d style=--x:url(#b)>x</p><label for=c>y</label>",
+    );
+    assert_eq!(map, name_map(&[("message", "b")], &[("main", "d")]));
+}
+
+#[test]
+fn test_mangle_js_strings() {
+    let map = name_map(&[("x", "a")], &[("y", "b")]);
+    assert_eq!(
+        String::from_utf8(mangle_js_strings(
+            &map,
+            b"q('.x', \"#y\", 'y', 'x y', `x`); // 'x'\nlet r = /'x'/; f(a / 2, 'x')"
+        ))
+        .unwrap(),
+        "q('.a', \"#b\", 'b', 'x y', `x`); // 'x'\nlet r = /'x'/; f(a / 2, 'a')",
+    );
+}
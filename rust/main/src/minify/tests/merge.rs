@@ -0,0 +1,30 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8]) {
+    let mut cfg = Cfg::new();
+    cfg.merge_formatting_elements = true;
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_merge_formatting_elements() {
+    eval(b"<p><b>foo</b><b>bar</b>", b"<p><b>foobar</b>");
+    eval(
+        b"<p><span class=x>a</span><span class=\"x\">b</span><span class=\"x\"><i>c</i></span><span class=\"x\"><i>d</i></span>",
+        b"<p><span class=x>ab<i>cd</i></span>",
+    );
+    eval(
+        b"<p><b>a</b> <b>b</b><b class=x>c</b><i>d</i><i>e</i>",
+        b"<p><b>a</b> <b>b</b><b class=x>c</b><i>de</i>",
+    );
+    eval(b"<div>a</div><div>b</div>", b"<div>a</div><div>b</div>");
+    eval(b"<p><q>a</q><q>b</q>", b"<p><q>a</q><q>b</q>");
+    eval(
+        b"<p><a href=x>a</a><a href=x>b</a>",
+        b"<p><a href=x>a</a><a href=x>b</a>",
+    );
+}
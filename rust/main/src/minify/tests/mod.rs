@@ -1 +1,19 @@
 mod attr;
+mod cache;
+mod class;
+mod comment;
+mod content;
+mod css;
+mod diagnostic;
+mod element;
+mod empty;
+mod esi;
+mod js;
+mod json;
+mod mangle;
+mod merge;
+mod script_html;
+mod sizes;
+mod srcset;
+mod url;
+mod whitespace_hints;
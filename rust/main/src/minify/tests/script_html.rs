@@ -0,0 +1,53 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval_with_cfg(src: &[u8], expected: &[u8], cfg: &Cfg) {
+    assert_eq!(
+        String::from_utf8(minify(src, cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+fn eval(src: &[u8], expected: &[u8]) {
+    eval_with_cfg(src, expected, &Cfg::new());
+}
+
+#[test]
+fn test_html_script_types() {
+    eval(
+        b"<script type=\"text/x-template\" id=\"item\">\n  <li class=\"item\">\n    {{ name }}\n  </li>\n</script>",
+        b"<script id=item type=text/x-template><li class=item>{{ name }}</li></script>",
+    );
+    eval(
+        b"<script type=\"text/ng-template\"> <p title=\"&lt;/script&gt;\">a</p> </script>",
+        b"<script type=text/ng-template> <p title=\"&lt;/script&gt;\">a</p> </script>",
+    );
+    eval(
+        b"<script type=\"text/plain\"> <p> a </p> </script>",
+        b"<script type=text/plain> <p> a </p> </script>",
+    );
+    let mut cfg = Cfg::new();
+    cfg.html_script_types.clear();
+    eval_with_cfg(
+        b"<script type=\"text/html\"> <p> a </p> </script>",
+        b"<script type=text/html> <p> a </p> </script>",
+        &cfg,
+    );
+}
+
+#[cfg(feature = "js-esbuild")]
+#[test]
+fn test_html_script_with_minified_style() {
+    let mut cfg = Cfg::new();
+    cfg.minify_css = true;
+    eval_with_cfg(
+        b"<script type=\"text/x-template\"> <style> a { color: red } </style> <p>a</p> </script>",
+        b"<script type=text/x-template><style>a{color:red}</style><p>a</p></script>",
+        &cfg,
+    );
+    eval_with_cfg(
+        b"<script type=\"text/x-template\"> <style> a::after { content: \"\\3C/script>\" } </style> </script>",
+        b"<script type=text/x-template> <style> a::after { content: \"\\3C/script>\" } </style> </script>",
+        &cfg,
+    );
+}
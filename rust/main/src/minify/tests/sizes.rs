@@ -0,0 +1,20 @@
+use crate::minify::sizes::minify_sizes;
+
+fn eval(src: &str, expected: Option<&str>) {
+    let min = minify_sizes(src.as_bytes());
+    assert_eq!(
+        min.as_ref().map(|m| std::str::from_utf8(m).unwrap()),
+        expected
+    );
+}
+
+#[test]
+fn test_minify_sizes() {
+    eval(
+        " ( max-width : 600px )  480px ,\n (min-width: 36em) and (max-width: 50em) 33vw, calc( 100vw - 2em ) ",
+        Some("(max-width:600px) 480px,(min-width:36em) and (max-width:50em) 33vw,calc(100vw - 2em)"),
+    );
+    eval("(max-width: 600px) 480px, (max-width: 800px", None);
+    eval("(max-width: 600px)) 480px", None);
+    eval("/* a */ 100vw", None);
+}
@@ -0,0 +1,53 @@
+use url::Url;
+
+use crate::minify::srcset::minify_srcset;
+
+fn eval(src: &str, expected: Option<&str>) {
+    let min = minify_srcset(None, src.as_bytes());
+    assert_eq!(
+        min.as_ref().map(|m| std::str::from_utf8(m).unwrap()),
+        expected
+    );
+}
+
+#[test]
+fn test_minify_srcset() {
+    eval(" a.png  1x ,\n b.png 2.0x ", Some("b.png 2x,a.png"));
+    eval(
+        "a.png, b.png 0.50x, c.png 1.5x",
+        Some("b.png .5x,c.png 1.5x,a.png"),
+    );
+    eval(
+        "a.png 100w, b.png 0200w 100h",
+        Some("a.png 100w,b.png 200w 100h"),
+    );
+    eval(
+        "a.png 2x, b.png 2x, c.png, d.png 1x",
+        Some("a.png 2x,c.png"),
+    );
+    eval("a,b.png 2x,,c.png,", Some("a,b.png 2x,c.png"));
+    eval("a.png 1e1x", Some("a.png 10x"));
+}
+
+#[test]
+fn test_minify_srcset_leaves_malformed_values() {
+    eval("", None);
+    eval("a.png 2x 100w", None);
+    eval("a.png 100w, b.png", None);
+    eval("a.png 100h", None);
+    eval("a.png -1x", None);
+    eval("a.png 0w", None);
+    eval("a.png 2y", None);
+    eval("a.png 2x (x)", None);
+}
+
+#[test]
+fn test_minify_srcset_minifies_urls() {
+    let base = Url::parse("https://example.com/img/").unwrap();
+    let min = minify_srcset(
+        Some(&base),
+        b"https://example.com/img/a.png 2x, https://example.com/img/b.png",
+    )
+    .unwrap();
+    assert_eq!(std::str::from_utf8(&min).unwrap(), "a.png 2x,b.png");
+}
@@ -0,0 +1,41 @@
+use url::Url;
+
+use crate::minify::url::minify_url;
+
+fn eval(base: &str, value: &str, expected: &str) {
+    let base = Url::parse(base).unwrap();
+    let min = minify_url(&base, value.as_bytes());
+    assert_eq!(std::str::from_utf8(&min).unwrap(), expected);
+}
+
+#[test]
+fn test_minify_url() {
+    let base = "https://example.com/a/b/page.html?x=1";
+    eval(base, "https://example.com/a/b/img.png", "img.png");
+    eval(base, "https://example.com/a/b/c/img.png", "c/img.png");
+    eval(base, "https://example.com/a/c/img.png", "/a/c/img.png");
+    eval(base, "https://example.com/img.png", "/img.png");
+    eval(
+        base,
+        "https://cdn.example.com/img.png",
+        "//cdn.example.com/img.png",
+    );
+    eval(
+        base,
+        "http://example.com/img.png",
+        "http://example.com/img.png",
+    );
+    eval(base, "https://example.com/a/b/page.html?x=2", "?x=2");
+    eval(base, "https://example.com/a/b/page.html?x=1#top", "#top");
+    eval(base, "https://example.com/a/b/", ".");
+    eval(base, "./img.png", "img.png");
+    eval(base, "https://example.com/a/b/c:d", "./c:d");
+    eval(base, "mailto:a@example.com", "mailto:a@example.com");
+    eval(base, "javascript:void(0)", "javascript:void(0)");
+    eval(base, "https://example.com/a/b/page.html?x=1", "?x=1");
+    eval(
+        "https://example.com/assets/css/main.css",
+        "https://example.com/assets/img/logo.png",
+        "../img/logo.png",
+    );
+}
@@ -0,0 +1,44 @@
+use crate::cfg::Cfg;
+use crate::minify;
+
+fn eval(src: &[u8], expected: &[u8]) {
+    let mut cfg = Cfg::new();
+    cfg.css_aware_whitespace = true;
+    assert_eq!(
+        String::from_utf8(minify(src, &cfg)).unwrap(),
+        String::from_utf8(expected.to_vec()).unwrap(),
+    );
+}
+
+#[test]
+fn test_css_aware_whitespace_inline_display() {
+    eval(
+        b"<style>nav li { display: inline-block }</style><ul> <li>a</li> <li>b</li> </ul>",
+        b"<style>nav li { display: inline-block }</style><ul><li>a</li> <li>b</li></ul>",
+    );
+    eval(
+        b"<style>@media (min-width: 1px) { .tab { display: inline } }</style><div> <div class=\"x tab\">a</div> <div>b</div> </div>",
+        b"<style>@media (min-width: 1px) { .tab { display: inline } }</style><div><div class=\"x tab\">a</div> <div>b</div></div>",
+    );
+    eval(
+        b"<div> <div style=\"display:inline-flex\"> a </div> <div>b</div> </div>",
+        b"<div><div style=display:inline-flex> a </div> <div>b</div></div>",
+    );
+    // Pseudo-elements and other properties don't affect the element itself.
+    eval(
+        b"<style>li::before { display: inline } li { color: red }</style><ul> <li>a</li> <li>b</li> </ul>",
+        b"<style>li::before { display: inline } li { color: red }</style><ul><li>a<li>b</ul>",
+    );
+}
+
+#[test]
+fn test_css_aware_whitespace_preserve() {
+    eval(
+        b"<style>.code { white-space: pre-wrap }</style><div class=code>  a\n\n  <span> b </span>  </div>",
+        b"<style>.code { white-space: pre-wrap }</style><div class=code>  a\n\n  <span> b </span>  </div>",
+    );
+    eval(
+        b"<p style=\"white-space: pre\">  a  </p>",
+        b"<p style=\"white-space: pre\">  a  ",
+    );
+}
@@ -0,0 +1,103 @@
+use std::str::from_utf8;
+
+use url::{Position, Url};
+
+use crate::ast::NodeData;
+use crate::cfg::Cfg;
+use minify_html_common::spec::tag::ns::Namespace;
+
+fn find_base_href(nodes: &[NodeData]) -> Option<&[u8]> {
+    for n in nodes {
+        if let NodeData::Element {
+            attributes,
+            children,
+            name,
+            namespace,
+            ..
+        } = n
+        {
+            if *namespace == Namespace::Html && name == b"base" {
+                if let Some(href) = attributes.get(b"href".as_ref()) {
+                    return Some(href);
+                };
+            };
+            if let Some(href) = find_base_href(children) {
+                return Some(href);
+            };
+        };
+    }
+    None
+}
+
+// Returns the URL that relative URLs in the document are resolved against, which is the `href` of the first `<base>`
+// element with one, or the document URL if there isn't one or it's invalid.
+// See https://html.spec.whatwg.org/multipage/urls-and-fetching.html#document-base-url.
+pub fn get_base_url(cfg: &Cfg, nodes: &[NodeData]) -> Option<Url> {
+    let document_url = Url::parse(cfg.document_url.as_ref()?).ok()?;
+    match find_base_href(nodes).and_then(|href| from_utf8(href).ok()) {
+        Some(href) => Some(document_url.join(href).unwrap_or(document_url)),
+        None => Some(document_url),
+    }
+}
+
+// Returns a path-relative form of `target`, using `../` to go up from the directory of `base`.
+fn path_relative(base: &Url, target: &Url) -> Option<String> {
+    let base_segments = base.path_segments()?.collect::<Vec<_>>();
+    let target_segments = target.path_segments()?.collect::<Vec<_>>();
+    // There is always at least one segment, which is empty if the path ends with a slash.
+    let base_dir = &base_segments[..base_segments.len() - 1];
+    let (target_dir, target_file) = target_segments.split_at(target_segments.len() - 1);
+    let common = base_dir
+        .iter()
+        .zip(target_dir)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut rel = String::new();
+    for _ in common..base_dir.len() {
+        rel.push_str("../");
+    }
+    for s in &target_dir[common..] {
+        rel.push_str(s);
+        rel.push('/');
+    }
+    rel.push_str(target_file[0]);
+    if rel.is_empty() {
+        rel.push('.');
+    } else if rel.split('/').next().unwrap().contains(':') {
+        // Otherwise, the first segment would be parsed as a scheme.
+        rel.insert_str(0, "./");
+    };
+    rel.push_str(&target[Position::AfterPath..]);
+    Some(rel)
+}
+
+// Rewrites a URL to the shortest equivalent form when resolved against `base`. Only forms that resolve to the exact
+// same URL as the original are considered, so values that aren't valid URLs are left as is.
+pub fn minify_url(base: &Url, value: &[u8]) -> Vec<u8> {
+    let target = match from_utf8(value).ok().and_then(|v| base.join(v).ok()) {
+        Some(t) => t,
+        None => return value.to_vec(),
+    };
+    let mut candidates = vec![target.as_str().to_string()];
+    // Scheme-relative.
+    candidates.push(target.as_str()[target.scheme().len() + 1..].to_string());
+    if target.has_authority() {
+        // Root-relative.
+        candidates.push(target[Position::BeforePath..].to_string());
+    };
+    if target[..Position::BeforePath] == base[..Position::BeforePath] {
+        candidates.extend(path_relative(base, &target));
+    };
+    // Same document.
+    candidates.push(target[Position::AfterPath..].to_string());
+    candidates.push(target[Position::AfterQuery..].to_string());
+
+    let mut min = value.to_vec();
+    for c in candidates {
+        // An empty value would make the attribute redundant.
+        if !c.is_empty() && c.len() < min.len() && base.join(&c).ok().as_ref() == Some(&target) {
+            min = c.into_bytes();
+        };
+    }
+    min
+}
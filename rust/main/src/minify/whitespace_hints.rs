@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use crate::ast::{NodeData, ScriptOrStyleLang};
+use minify_html_common::gen::codepoints::WHITESPACE;
+
+// What the CSS in a document indicates about how whitespace in and around an element is rendered.
+// Hints are only used to minify whitespace less, so they don't need to be precise as long as they are never missed.
+#[derive(Copy, Clone, Default)]
+pub struct WhitespaceHint {
+    // The element has an inline-level `display` value, so whitespace around it and at the start and end of its content
+    // may be rendered.
+    pub inline: bool,
+    // The element has a `white-space` value that preserves whitespace, which is inherited by its descendants.
+    pub preserve: bool,
+}
+
+impl WhitespaceHint {
+    fn merge(&mut self, other: WhitespaceHint) {
+        self.inline |= other.inline;
+        self.preserve |= other.preserve;
+    }
+
+    fn is_some(&self) -> bool {
+        self.inline || self.preserve
+    }
+}
+
+// Hints from type and class selectors in the document's `<style>` blocks.
+#[derive(Default)]
+pub struct StylesheetHints {
+    by_tag: HashMap<Vec<u8>, WhitespaceHint>,
+    by_class: HashMap<Vec<u8>, WhitespaceHint>,
+}
+
+fn trim(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|&c| !WHITESPACE[c]).unwrap_or(s.len());
+    let end = s
+        .iter()
+        .rposition(|&c| !WHITESPACE[c])
+        .map_or(start, |i| i + 1);
+    &s[start..end]
+}
+
+fn strip_comments(code: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(code.len());
+    let mut i = 0;
+    while i < code.len() {
+        if code[i..].starts_with(b"/*") {
+            match code[i + 2..].windows(2).position(|w| w == b"*/") {
+                Some(end) => i += end + 4,
+                None => break,
+            };
+            // Comments separate tokens.
+            out.push(b' ');
+        } else {
+            out.push(code[i]);
+            i += 1;
+        };
+    }
+    out
+}
+
+// Parses a CSS declaration list, such as the value of a `style` attribute.
+fn parse_declarations(code: &[u8]) -> WhitespaceHint {
+    let mut hint = WhitespaceHint::default();
+    for decl in code.split(|&c| c == b';') {
+        let colon = match decl.iter().position(|&c| c == b':') {
+            Some(colon) => colon,
+            None => continue,
+        };
+        let prop = trim(&decl[..colon]).to_ascii_lowercase();
+        let val = decl[colon + 1..].to_ascii_lowercase();
+        let mut keywords = val
+            .split(|&c| WHITESPACE[c] || c == b'!')
+            .filter(|k| !k.is_empty());
+        match prop.as_slice() {
+            b"display" => {
+                hint.inline |= keywords.any(|k| k.starts_with(b"inline") || k == b"contents");
+            }
+            b"white-space" | b"white-space-collapse" => {
+                hint.preserve |= keywords.any(|k| {
+                    matches!(
+                        k,
+                        b"pre" | b"pre-wrap" | b"pre-line" | b"break-spaces" | b"preserve"
+                    ) || k.starts_with(b"preserve-")
+                });
+            }
+            _ => {}
+        };
+    }
+    hint
+}
+
+// Returns the index of the next `{` or `;` at the top level, skipping over any strings.
+fn find_block_or_statement_end(code: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (i, &c) in code.iter().enumerate() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(c),
+            (None, b'{' | b';') => return Some(i),
+            _ => {}
+        };
+    }
+    None
+}
+
+// Returns the index of the `}` that closes a block whose contents start at `code[0]`.
+fn find_block_end(code: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, &c) in code.iter().enumerate() {
+        match c {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => {}
+        };
+    }
+    None
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80
+}
+
+impl StylesheetHints {
+    fn add_selector(&mut self, selector: &[u8], hint: WhitespaceHint) {
+        // Only the last compound selector matches the element the declarations apply to.
+        let subject = selector
+            .rsplit(|&c| WHITESPACE[c] || c == b'>' || c == b'+' || c == b'~')
+            .find(|s| !s.is_empty())
+            .unwrap_or(b"");
+        let lower = subject.to_ascii_lowercase();
+        // Declarations for pseudo-elements don't apply to the element itself.
+        if lower.windows(2).any(|w| w == b"::")
+            || [
+                b":before".as_ref(),
+                b":after",
+                b":first-line",
+                b":first-letter",
+            ]
+            .iter()
+            .any(|p| lower.windows(p.len()).any(|w| w == *p))
+        {
+            return;
+        };
+        let tag_len = subject.iter().take_while(|&&c| is_ident_char(c)).count();
+        if tag_len > 0 {
+            self.by_tag
+                .entry(lower[..tag_len].to_vec())
+                .or_default()
+                .merge(hint);
+        };
+        let mut rest = &subject[tag_len..];
+        while let Some(dot) = rest.iter().position(|&c| c == b'.') {
+            rest = &rest[dot + 1..];
+            let class_len = rest.iter().take_while(|&&c| is_ident_char(c)).count();
+            if class_len > 0 {
+                self.by_class
+                    .entry(rest[..class_len].to_vec())
+                    .or_default()
+                    .merge(hint);
+            };
+        }
+    }
+
+    // Parses a list of rules. Rules in conditional at-rules like `@media` are treated as if they always apply.
+    fn add_rules(&mut self, mut code: &[u8]) {
+        while let Some(end) = find_block_or_statement_end(code) {
+            let prelude = trim(&code[..end]);
+            if code[end] == b';' {
+                code = &code[end + 1..];
+                continue;
+            };
+            let block_end = match find_block_end(&code[end + 1..]) {
+                Some(block_end) => end + 1 + block_end,
+                None => code.len(),
+            };
+            let block = &code[end + 1..block_end];
+            if prelude.starts_with(b"@") {
+                let name = prelude
+                    .iter()
+                    .skip(1)
+                    .take_while(|&&c| is_ident_char(c))
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect::<Vec<_>>();
+                if matches!(
+                    name.as_slice(),
+                    b"media" | b"supports" | b"layer" | b"container" | b"document"
+                ) {
+                    self.add_rules(block);
+                };
+            } else {
+                let hint = parse_declarations(block);
+                if hint.is_some() {
+                    for selector in prelude.split(|&c| c == b',') {
+                        self.add_selector(trim(selector), hint);
+                    }
+                };
+            };
+            code = code.get(block_end + 1..).unwrap_or(b"");
+        }
+    }
+
+    fn add_document(&mut self, nodes: &[NodeData]) {
+        for n in nodes {
+            match n {
+                NodeData::Element { children, .. } => self.add_document(children),
+                NodeData::ScriptOrStyleContent {
+                    code,
+                    lang: ScriptOrStyleLang::CSS,
+                    ..
+                } => self.add_rules(&strip_comments(code)),
+                _ => {}
+            };
+        }
+    }
+}
+
+pub fn get_stylesheet_hints(nodes: &[NodeData]) -> StylesheetHints {
+    let mut hints = StylesheetHints::default();
+    hints.add_document(nodes);
+    hints
+}
+
+pub fn get_element_hint(
+    stylesheet: &StylesheetHints,
+    tag_name: &[u8],
+    attributes: &HashMap<Vec<u8>, Vec<u8>>,
+) -> WhitespaceHint {
+    let mut hint = stylesheet.by_tag.get(tag_name).copied().unwrap_or_default();
+    if let Some(class) = attributes.get(b"class".as_ref()) {
+        for c in class.split(|&c| WHITESPACE[c]).filter(|c| !c.is_empty()) {
+            if let Some(h) = stylesheet.by_class.get(c) {
+                hint.merge(*h);
+            };
+        }
+    };
+    if let Some(style) = attributes.get(b"style".as_ref()) {
+        hint.merge(parse_declarations(&strip_comments(style)));
+    };
+    hint
+}
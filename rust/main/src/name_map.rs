@@ -0,0 +1,19 @@
+use std::collections::HashMap;
+
+/// The short names that class names and IDs are renamed to when mangling them using
+/// [`minify_with_name_map`](crate::minify_with_name_map). Providing the same map when minifying
+/// different documents, or when processing separate CSS and JS bundles, keeps the renaming
+/// consistent.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NameMap {
+    /// Original class names mapped to their mangled names.
+    pub classes: HashMap<String, String>,
+    /// Original IDs mapped to their mangled IDs.
+    pub ids: HashMap<String, String>,
+}
+
+impl NameMap {
+    pub fn new() -> NameMap {
+        NameMap::default()
+    }
+}
@@ -10,6 +10,7 @@ lazy_static! {
 
 pub fn parse_comment(code: &mut Code) -> NodeData {
     debug_assert!(code.as_slice().starts_with(b"<!--"));
+    let start = code.position();
     code.shift(4);
     let (len, matched) = match COMMENT_END.find(code.as_slice()) {
         Some(m) => (m.start(), m.end() - m.start()),
@@ -21,5 +22,6 @@ pub fn parse_comment(code: &mut Code) -> NodeData {
     NodeData::Comment {
         code: data,
         ended: matched > 0,
+        span: start..code.position(),
     }
 }
@@ -11,6 +11,7 @@ use crate::parse::element::{parse_element, parse_tag, peek_tag_name};
 use crate::parse::instruction::parse_instruction;
 use crate::parse::Code;
 use minify_html_common::gen::codepoints::TAG_NAME_CHAR;
+use minify_html_common::spec::tag::esi::is_esi_tag;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::omission::{can_omit_as_before, can_omit_as_last_node};
 use minify_html_common::spec::tag::void::VOID_TAGS;
@@ -162,6 +163,15 @@ pub fn parse_content(
                     // The upcoming closing tag implicitly closes the current element e.g. `<tr><td>(current position)</tr>`.
                     // This DOESN'T handle when grandparent doesn't exist (represented by an empty slice). However, in that case it's irrelevant, as it would mean we would be at EOF, and our parser simply auto-closes everything anyway. (Normally we'd have to determine if `<p>Hello` is an error or allowed.)
                     typ = OmittedClosingTag;
+                } else if is_esi_tag(&name) && parent != name.as_slice() {
+                    // ESI elements are processed before the document is parsed as HTML, so an ESI closing tag closes
+                    // any elements that are still open within the ESI element, e.g. `<esi:remove><div>(current position)</esi:remove>`.
+                    // An ESI closing tag without an open ESI element of the same name is dropped.
+                    typ = if code.open_esi_elements.contains(&name) {
+                        OmittedClosingTag
+                    } else {
+                        IgnoredTag
+                    };
                 } else if VOID_TAGS.contains(name.as_slice()) {
                     // Closing tag for void element, drop.
                     typ = IgnoredTag;
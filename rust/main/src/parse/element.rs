@@ -12,7 +12,10 @@ use minify_html_common::gen::codepoints::{
     ATTR_QUOTE, DOUBLE_QUOTE, NOT_UNQUOTED_ATTR_VAL_CHAR, SINGLE_QUOTE, TAG_NAME_CHAR, WHITESPACE,
     WHITESPACE_OR_SLASH, WHITESPACE_OR_SLASH_OR_EQUALS_OR_RIGHT_CHEVRON,
 };
-use minify_html_common::spec::script::JAVASCRIPT_MIME_TYPES;
+use minify_html_common::spec::script::{
+    is_module_script_type, JAVASCRIPT_MIME_TYPES, JSON_SCRIPT_TYPES,
+};
+use minify_html_common::spec::tag::esi::is_esi_tag;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::void::VOID_TAGS;
 use std::fmt::{Debug, Formatter};
@@ -125,14 +128,15 @@ pub fn parse_tag(code: &mut Code) -> ParsedTag {
 // `<` or `</` must be next. If `</` is next, tag is reinterpreted as opening tag (i.e. `/` is ignored).
 // `parent` should be an empty slice if it doesn't exist.
 pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData {
+    let start = code.position();
     let ParsedTag {
         name: elem_name,
         attributes,
         self_closing,
     } = parse_tag(code);
 
-    // Only foreign elements can be self closed.
-    if self_closing && ns != Namespace::Html {
+    // Only foreign elements and ESI elements can be self closed.
+    if self_closing && (ns != Namespace::Html || is_esi_tag(&elem_name)) {
         return NodeData::Element {
             attributes,
             children: Vec::new(),
@@ -140,6 +144,7 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
             name: elem_name,
             namespace: ns,
             next_sibling_element_name: Vec::new(),
+            span: start..code.position(),
         };
     };
     if VOID_TAGS.contains(elem_name.as_slice()) {
@@ -150,6 +155,7 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
             name: elem_name,
             namespace: ns,
             next_sibling_element_name: Vec::new(),
+            span: start..code.position(),
         };
     };
 
@@ -166,6 +172,12 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
         children,
     } = match elem_name.as_slice() {
         b"script" => match attributes.get(b"type".as_ref()) {
+            Some(mime) if JSON_SCRIPT_TYPES.contains(mime.as_slice()) => {
+                parse_script_content(code, ScriptOrStyleLang::Json)
+            }
+            Some(mime) if is_module_script_type(mime) => {
+                parse_script_content(code, ScriptOrStyleLang::JSModule)
+            }
             Some(mime) if !JAVASCRIPT_MIME_TYPES.contains(mime.as_slice()) => {
                 parse_script_content(code, ScriptOrStyleLang::Data)
             }
@@ -174,6 +186,12 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
         b"style" => parse_style_content(code),
         b"textarea" => parse_textarea_content(code),
         b"title" => parse_title_content(code),
+        _ if is_esi_tag(&elem_name) => {
+            code.open_esi_elements.push(elem_name.clone());
+            let parsed = parse_content(code, child_ns, parent, &elem_name);
+            code.open_esi_elements.pop();
+            parsed
+        }
         _ => parse_content(code, child_ns, parent, &elem_name),
     };
 
@@ -193,5 +211,6 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
         name: elem_name,
         namespace: ns,
         next_sibling_element_name: Vec::new(),
+        span: start..code.position(),
     }
 }
@@ -15,11 +15,16 @@ pub mod title;
 pub struct Code<'c> {
     code: &'c [u8],
     next: usize,
+    // The position of `code` in the source, if it's part of a larger document, so that positions in the source can be
+    // recorded.
+    offset: usize,
 
     pub seen_html_open: bool,
     pub seen_head_open: bool,
     pub seen_head_close: bool,
     pub seen_body_open: bool,
+    // The names of ESI elements that are currently open, from outermost to innermost.
+    pub open_esi_elements: Vec<Vec<u8>>,
 }
 
 #[derive(Copy, Clone)]
@@ -27,13 +32,19 @@ pub struct Checkpoint(usize);
 
 impl<'c> Code<'c> {
     pub fn new(code: &[u8]) -> Code {
+        Code::new_at(code, 0)
+    }
+
+    pub fn new_at(code: &[u8], offset: usize) -> Code<'_> {
         Code {
             code,
             next: 0,
+            offset,
             seen_html_open: false,
             seen_head_open: false,
             seen_head_close: false,
             seen_body_open: false,
+            open_esi_elements: Vec::new(),
         }
     }
 
@@ -41,6 +52,11 @@ impl<'c> Code<'c> {
         &self.code[self.next..]
     }
 
+    // The position of the next character in the source.
+    pub fn position(&self) -> usize {
+        self.offset + self.next
+    }
+
     pub fn take_checkpoint(&self) -> Checkpoint {
         Checkpoint(self.next)
     }
@@ -17,11 +17,13 @@ pub fn parse_script_content(code: &mut Code, lang: ScriptOrStyleLang) -> ParsedC
         Some(m) => (m.start(), false),
         None => (code.rem(), true),
     };
+    let start = code.position();
     ParsedContent {
         closing_tag_omitted,
         children: vec![NodeData::ScriptOrStyleContent {
             code: code.copy_and_shift(len),
             lang,
+            span: start..code.position(),
         }],
     }
 }
@@ -17,11 +17,13 @@ pub fn parse_style_content(code: &mut Code) -> ParsedContent {
         Some(m) => (m.start(), false),
         None => (code.rem(), true),
     };
+    let start = code.position();
     ParsedContent {
         closing_tag_omitted,
         children: vec![NodeData::ScriptOrStyleContent {
             code: code.copy_and_shift(len),
             lang: ScriptOrStyleLang::CSS,
+            span: start..code.position(),
         }],
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::ast::{ElementClosingTag, NodeData};
+use crate::ast::{ElementClosingTag, NodeData, ScriptOrStyleLang};
 use crate::parse::element::{parse_element, parse_tag, ParsedTag};
 use crate::parse::Code;
 use minify_html_common::spec::tag::ns::Namespace;
@@ -59,6 +59,44 @@ fn test_parse_element() {
             name: b"a".to_vec(),
             namespace: Namespace::Html,
             next_sibling_element_name: Vec::new(),
+            span: 0..15,
         }
     );
 }
+
+#[test]
+fn test_parse_script_lang() {
+    for (src, lang) in [
+        (b"<script>a</script>".as_ref(), ScriptOrStyleLang::JS),
+        (
+            b"<script type=module>a</script>",
+            ScriptOrStyleLang::JSModule,
+        ),
+        (
+            b"<script type=MODULE>a</script>",
+            ScriptOrStyleLang::JSModule,
+        ),
+        (
+            b"<script type=importmap>a</script>",
+            ScriptOrStyleLang::Json,
+        ),
+        (
+            b"<script type=text/plain>a</script>",
+            ScriptOrStyleLang::Data,
+        ),
+    ] {
+        let mut code = Code::new(src);
+        let start = src.iter().position(|&c| c == b'>').unwrap() + 1;
+        match parse_element(&mut code, Namespace::Html, EMPTY_SLICE) {
+            NodeData::Element { children, .. } => assert_eq!(
+                children,
+                vec![NodeData::ScriptOrStyleContent {
+                    code: b"a".to_vec(),
+                    lang,
+                    span: start..start + 1,
+                }]
+            ),
+            _ => unreachable!(),
+        };
+    }
+}
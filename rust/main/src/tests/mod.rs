@@ -0,0 +1,9 @@
+use crate::Cfg;
+
+fn minify(code: &[u8], cfg: &Cfg) -> Vec<u8> {
+    crate::minify(code, cfg)
+}
+
+// These tests are shared with the onepass crate, so that both are tested against the same fixtures.
+#[path = "../../../common/src/tests/mod.rs"]
+mod shared;
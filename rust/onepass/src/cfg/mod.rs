@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+
+use minify_html_common::esbuild::{CssOptions, JsOptions};
+use minify_html_common::spec::tag::whitespace::WhitespaceMinification;
+
 /// Configuration settings that can be adjusted and passed to a minification function to change the
 /// minification approach.
 pub struct Cfg {
@@ -10,8 +15,78 @@ pub struct Cfg {
     /// contain JavaScript, as per the specification.
     pub minify_js: bool,
 
+    /// Options passed to esbuild when minifying JavaScript, if `minify_js` is enabled. Legal
+    /// comments are kept by default, and non-ASCII characters are always escaped.
+    pub js_options: JsOptions,
+
     /// If enabled, CSS in `<style>` tags are minified using
     /// [esbuild-rs](https://github.com/wilsonzlin/esbuild-rs). The `js-esbuild` feature must be
     /// enabled; otherwise, this value has no effect.
     pub minify_css: bool,
+
+    /// Options passed to esbuild when minifying CSS, if `minify_css` is enabled. Legal comments
+    /// are kept by default, and non-ASCII characters are always escaped.
+    pub css_options: CssOptions,
+
+    /// Ensure all unquoted attribute values in the output do not contain any characters prohibited by the [WHATWG specification](https://html.spec.whatwg.org/multipage/syntax.html#attributes-2).
+    ///
+    /// As code is minified in place, such a value can only be quoted if minifying the code before
+    /// it saved enough space for the quotes. Otherwise, minification fails with
+    /// [`ErrorType::NonCompliantUnquotedAttributeValue`](crate::ErrorType::NonCompliantUnquotedAttributeValue).
+    pub ensure_spec_compliant_unquoted_attribute_values: bool,
+
+    /// Do not omit closing tags when possible.
+    pub keep_closing_tags: bool,
+
+    /// Keep all comments.
+    pub keep_comments: bool,
+
+    /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes.
+    pub keep_html_and_head_opening_tags: bool,
+
+    /// Keep spaces between attributes when possible to conform to HTML standards. As code is
+    /// minified in place, a space is only kept if there is whitespace between the attributes in
+    /// the source.
+    pub keep_spaces_between_attributes: bool,
+
+    /// Remove all bangs.
+    pub remove_bangs: bool,
+
+    /// Remove all processing_instructions.
+    pub remove_processing_instructions: bool,
+
+    /// Override how whitespace is minified in the content of elements with these tag names, which
+    /// must be lowercase. This can be used for custom elements or elements styled with a different
+    /// `display` value. The content of `<pre>` and its descendants is never minified.
+    pub whitespace_minification_overrides: HashMap<Vec<u8>, WhitespaceMinification>,
+}
+
+impl Cfg {
+    pub fn new() -> Cfg {
+        Cfg {
+            minify_js: false,
+            js_options: JsOptions {
+                keep_legal_comments: true,
+                ..JsOptions::default()
+            },
+            minify_css: false,
+            css_options: CssOptions {
+                keep_legal_comments: true,
+            },
+            ensure_spec_compliant_unquoted_attribute_values: false,
+            keep_closing_tags: false,
+            keep_comments: false,
+            keep_html_and_head_opening_tags: false,
+            keep_spaces_between_attributes: false,
+            remove_bangs: false,
+            remove_processing_instructions: false,
+            whitespace_minification_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl Default for Cfg {
+    fn default() -> Cfg {
+        Cfg::new()
+    }
 }
@@ -5,6 +5,7 @@ pub enum ErrorType {
     NotFound(&'static str),
     UnexpectedEnd,
     UnexpectedClosingTag,
+    NonCompliantUnquotedAttributeValue,
 }
 
 impl ErrorType {
@@ -26,6 +27,9 @@ impl ErrorType {
             ErrorType::UnexpectedClosingTag => {
                 format!("Unexpected closing tag.")
             }
+            ErrorType::NonCompliantUnquotedAttributeValue => {
+                "Unquoted attribute value contains characters not allowed by the specification and is too short to quote.".to_string()
+            }
         }
     }
 }
@@ -3,12 +3,16 @@ use crate::err::debug_repr;
 pub use crate::err::{Error, ErrorType, FriendlyError};
 use crate::proc::Processor;
 use crate::unit::content::process_content;
+pub use minify_html_common::esbuild::{CssOptions, EsTarget, JsOptions};
 use minify_html_common::spec::tag::ns::Namespace;
+pub use minify_html_common::spec::tag::whitespace::WhitespaceMinification;
 
 mod cfg;
 mod err;
 #[macro_use]
 mod proc;
+#[cfg(test)]
+mod tests;
 mod unit;
 
 /// Minifies a slice in-place and returns the new minified length.
@@ -28,6 +32,7 @@ mod unit;
 /// let cfg = &Cfg {
 ///     minify_js: false,
 ///     minify_css: false,
+///     ..Cfg::new()
 /// };
 /// match in_place(&mut code, cfg) {
 ///     Ok(minified_len) => assert_eq!(&code, b"<p>Hello, world!d!  </p>"),
@@ -68,6 +73,7 @@ pub fn in_place(code: &mut [u8], cfg: &Cfg) -> Result<usize, Error> {
 /// let cfg = &Cfg {
 ///     minify_js: false,
 ///     minify_css: false,
+///     ..Cfg::new()
 /// };
 /// match in_place_str(&mut code, cfg) {
 ///     Ok(minified_len) => assert_eq!(&code, "<p>Hello, world!d!  </p>"),
@@ -98,6 +104,7 @@ pub fn in_place_str<'s>(code: &'s mut str, cfg: &Cfg) -> Result<&'s str, Error>
 /// let cfg = &Cfg {
 ///     minify_js: false,
 ///     minify_css: false,
+///     ..Cfg::new()
 /// };
 /// match truncate(&mut code, cfg) {
 ///     Ok(()) => assert_eq!(code, b"<p>Hello, world!".to_vec()),
@@ -131,6 +138,7 @@ pub fn truncate(code: &mut Vec<u8>, cfg: &Cfg) -> Result<(), Error> {
 /// let cfg = &Cfg {
 ///     minify_js: false,
 ///     minify_css: false,
+///     ..Cfg::new()
 /// };
 /// match copy(&code, cfg) {
 ///     Ok(minified) => {
@@ -170,6 +178,7 @@ pub fn copy(code: &[u8], cfg: &Cfg) -> Result<Vec<u8>, Error> {
 /// let cfg = &Cfg {
 ///     minify_js: false,
 ///     minify_css: false,
+///     ..Cfg::new()
 /// };
 /// match with_friendly_error(&mut code, cfg) {
 ///     Ok(minified_len) => {}
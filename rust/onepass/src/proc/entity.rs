@@ -114,8 +114,10 @@ fn parse_entity(code: &mut [u8], read_pos: usize, write_pos: usize, in_attr_val:
             ),
             EntityType::Named(decoded) => {
                 // https://html.spec.whatwg.org/multipage/parsing.html#named-character-reference-state.
-                // TODO Generated trie no longer contains encoded values, even if longer.
+                // The generated trie always contains decoded values, so check if they're longer, as decoding in place
+                // can't write more than was read.
                 if decoded[0] == b'&' && decoded.len() > 1
+                    || decoded.len() > match_len
                     || in_attr_val
                         && *code.get(read_pos + match_len - 1).unwrap() != b';'
                         && code
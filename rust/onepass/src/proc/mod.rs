@@ -7,7 +7,9 @@ use memchr::memchr;
 
 #[cfg(feature = "js-esbuild")]
 use {
+    crate::cfg::Cfg,
     crossbeam::sync::WaitGroup,
+    minify_html_common::esbuild::EsbuildTransformOptions,
     std::sync::{Arc, Mutex},
 };
 
@@ -45,6 +47,7 @@ pub enum MatchMode {
     ThroughSeq(&'static AhoCorasick),
 }
 
+#[derive(Clone, Copy)]
 pub enum MatchAction {
     Keep,
     Discard,
@@ -64,10 +67,18 @@ pub struct Processor<'d> {
     read_next: usize,
     // Index of the next unwritten space.
     write_next: usize,
+    // Whether the code is part of a larger document, such as the content of a conditional comment, so the closing tags
+    // of elements at the end can't be omitted.
+    fragment: bool,
+    // Ranges of written code to remove when finishing, in order.
+    omitted: Vec<ProcessorRange>,
     #[cfg(feature = "js-esbuild")]
     esbuild_wg: WaitGroup,
     #[cfg(feature = "js-esbuild")]
     esbuild_results: Arc<Mutex<Vec<EsbuildSection>>>,
+    // Built when first needed, so documents without scripts or styles don't build them.
+    #[cfg(feature = "js-esbuild")]
+    esbuild_options: Option<Arc<EsbuildTransformOptions>>,
 }
 
 impl<'d> Index<ProcessorRange> for Processor<'d> {
@@ -95,11 +106,23 @@ impl<'d> Processor<'d> {
         Processor {
             write_next: 0,
             read_next: 0,
+            fragment: false,
+            omitted: Vec::new(),
             code,
             #[cfg(feature = "js-esbuild")]
             esbuild_wg: WaitGroup::new(),
             #[cfg(feature = "js-esbuild")]
             esbuild_results: Arc::new(Mutex::new(Vec::new())),
+            #[cfg(feature = "js-esbuild")]
+            esbuild_options: None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn new_fragment(code: &mut [u8]) -> Processor<'_> {
+        Processor {
+            fragment: true,
+            ..Processor::new(code)
         }
     }
 
@@ -234,6 +257,11 @@ impl<'d> Processor<'d> {
     }
 
     // PUBLIC APIs.
+    #[inline(always)]
+    pub fn is_fragment(&self) -> bool {
+        self.fragment
+    }
+
     // Bounds checking
     #[inline(always)]
     pub fn at_end(&self) -> bool {
@@ -261,11 +289,24 @@ impl<'d> Processor<'d> {
         self.read_next
     }
 
+    /// Get how many characters have been written to output.
+    #[inline(always)]
+    pub fn write_len(&self) -> usize {
+        self.write_next
+    }
+
     #[inline(always)]
     pub fn reserve_output(&mut self, amount: usize) -> () {
         self.write_next += amount;
     }
 
+    /// Remove written code when finishing, for code that must still be readable until then.
+    #[inline(always)]
+    pub fn omit_written(&mut self, range: ProcessorRange) {
+        debug_assert!(range.end <= self.write_next);
+        self.omitted.push(range);
+    }
+
     // Looking ahead.
     /// Get the `offset` character from next.
     /// When `offset` is 0, the next character is returned.
@@ -380,6 +421,20 @@ impl<'d> Processor<'d> {
         self._shift(count);
     }
 
+    // Non-ASCII characters are always escaped, as that's what esbuild does by default.
+    #[cfg(feature = "js-esbuild")]
+    pub fn esbuild_options(&mut self, cfg: &Cfg) -> Arc<EsbuildTransformOptions> {
+        self.esbuild_options
+            .get_or_insert_with(|| {
+                Arc::new(EsbuildTransformOptions::new(
+                    &cfg.js_options,
+                    &cfg.css_options,
+                    true,
+                ))
+            })
+            .clone()
+    }
+
     #[cfg(feature = "js-esbuild")]
     #[inline(always)]
     pub fn new_esbuild_section(&self) -> (WaitGroup, Arc<Mutex<Vec<EsbuildSection>>>) {
@@ -391,7 +446,14 @@ impl<'d> Processor<'d> {
     #[inline(always)]
     pub fn finish(self) -> Result<usize, Error> {
         debug_assert!(self.at_end());
-        Ok(self.write_next)
+        // Shift code between omitted ranges towards the left.
+        let mut write_next = self.omitted.first().map_or(self.write_next, |r| r.start);
+        for (i, omitted) in self.omitted.iter().enumerate() {
+            let next_start = self.omitted.get(i + 1).map_or(self.write_next, |r| r.start);
+            self.code.copy_within(omitted.end..next_start, write_next);
+            write_next += next_start - omitted.end;
+        }
+        Ok(write_next)
     }
 
     // Since we consume the Processor, we must provide a full Error with positions.
@@ -404,6 +466,11 @@ impl<'d> Processor<'d> {
             .unwrap_or_else(|_| panic!("failed to acquire esbuild results"))
             .into_inner()
             .unwrap();
+        // Omitted ranges are replaced with nothing, like JS/CSS that has been minified away.
+        results.extend(self.omitted.iter().map(|&src| EsbuildSection {
+            src,
+            escaped: Vec::new(),
+        }));
         results.sort_unstable_by_key(|r| r.src.start);
         // As we write minified JS/CSS code for sections from left to right, we will be shifting code
         // towards the left as previous source JS/CSS code sections shrink. We need to keep track of
@@ -0,0 +1,24 @@
+use crate::{Cfg, ErrorType};
+
+fn minify(code: &[u8], cfg: &Cfg) -> Vec<u8> {
+    crate::copy(code, cfg).unwrap()
+}
+
+// These tests are shared with the main crate, so that both are tested against the same fixtures.
+#[path = "../../../common/src/tests/mod.rs"]
+mod shared;
+
+#[test]
+fn test_attr_whatwg_unquoted_value_without_room_to_quote() {
+    let mut cfg = Cfg::new();
+    cfg.ensure_spec_compliant_unquoted_attribute_values = true;
+    assert_eq!(
+        crate::copy(b"<a b==></a>", &cfg).unwrap_err().error_type,
+        ErrorType::NonCompliantUnquotedAttributeValue
+    );
+    // Quoting is possible if minifying the rest of the tag saves enough space.
+    assert_eq!(
+        crate::copy(b"<a   b==></a>", &cfg).unwrap(),
+        br#"<a b="="></a>"#.to_vec()
+    );
+}
@@ -1,3 +1,4 @@
+use crate::cfg::Cfg;
 use crate::err::ProcessingResult;
 use crate::proc::checkpoint::WriteCheckpoint;
 use crate::proc::range::ProcessorRange;
@@ -28,6 +29,7 @@ pub struct ProcessedAttr {
 
 pub fn process_attr(
     proc: &mut Processor,
+    cfg: &Cfg,
     ns: Namespace,
     element: ProcessorRange,
 ) -> ProcessingResult<ProcessedAttr> {
@@ -57,7 +59,11 @@ pub fn process_attr(
             after_name.erase_written(proc);
             (AttrType::NoValue, None)
         } else {
-            match process_attr_value(proc, should_collapse_and_trim_value_ws)? {
+            match process_attr_value(
+                proc,
+                should_collapse_and_trim_value_ws,
+                cfg.ensure_spec_compliant_unquoted_attribute_values,
+            )? {
                 ProcessedAttrValue { value: None, .. } => {
                     // Value is empty, which is equivalent to no value, so discard `=`.
                     debug_assert_eq!(after_name.written_count(proc), 1);
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use lazy_static::lazy_static;
 
-use crate::err::ProcessingResult;
+use crate::err::{ErrorType, ProcessingResult};
 use crate::proc::checkpoint::WriteCheckpoint;
 use crate::proc::entity::maybe_normalise_entity;
 use crate::proc::range::ProcessorRange;
@@ -138,7 +138,11 @@ impl Metrics {
         raw_len
     }
 
-    fn get_optimal_delimiter_type(&self, raw_val: &[u8]) -> (DelimiterType, usize) {
+    fn get_optimal_delimiter_type(
+        &self,
+        raw_val: &[u8],
+        spec_compliant_unquoted: bool,
+    ) -> (DelimiterType, usize) {
         // When all equal, prefer double quotes to all and single quotes to unquoted.
         let mut min = (DelimiterType::Double, self.double_quoted_len(raw_val.len()));
 
@@ -147,8 +151,13 @@ impl Metrics {
             min = single;
         };
 
+        // Characters prohibited in unquoted values by the spec are never encoded, so the value must be quoted instead.
+        let unquoted_allowed = !spec_compliant_unquoted
+            || !raw_val
+                .iter()
+                .any(|c| matches!(c, b'"' | b'\'' | b'<' | b'=' | b'`'));
         let unquoted = (DelimiterType::Unquoted, self.unquoted_len(raw_val));
-        if unquoted.1 < min.1 {
+        if unquoted_allowed && unquoted.1 < min.1 {
             min = unquoted;
         };
 
@@ -202,6 +211,8 @@ fn handle_whitespace_char_type(c: u8, proc: &mut Processor, metrics: &mut Metric
 pub fn process_attr_value(
     proc: &mut Processor,
     should_collapse_and_trim_ws: bool,
+    // Whether unquoted values must not contain characters prohibited by the WHATWG specification.
+    spec_compliant_unquoted: bool,
 ) -> ProcessingResult<ProcessedAttrValue> {
     let start = WriteCheckpoint::new(proc);
     let src_delimiter = proc.m(IsInLookup(ATTR_QUOTE), Discard).first(proc);
@@ -318,13 +329,19 @@ pub fn process_attr_value(
 
     // Stage 2: optimally minify attribute value using metrics.
     // TODO Optimise: don't do anything if minimum is already optimal.
-    let (optimal_delimiter, optimal_len) = metrics.get_optimal_delimiter_type(&proc[minimum_value]);
+    let (optimal_delimiter, optimal_len) =
+        metrics.get_optimal_delimiter_type(&proc[minimum_value], spec_compliant_unquoted);
     let optimal_delimiter_char = match optimal_delimiter {
         DelimiterType::Double => Some(b'"'),
         DelimiterType::Single => Some(b'\''),
         _ => None,
     };
 
+    // As code is minified in place, the value can't be longer than its source, which only happens when an unquoted value
+    // with characters not allowed by the specification must be quoted.
+    if proc.write_len() + (optimal_len - minimum_value.len()) > proc.read_len() {
+        return Err(ErrorType::NonCompliantUnquotedAttributeValue);
+    };
     proc.reserve_output(optimal_len - minimum_value.len());
     let optimal_slice = &mut proc[start.get_written_range_since(optimal_len)];
     let mut write = optimal_slice.len() - 1;
@@ -1,11 +1,22 @@
+use crate::cfg::Cfg;
 use crate::err::ProcessingResult;
+use crate::proc::checkpoint::WriteCheckpoint;
 use crate::proc::MatchAction::*;
 use crate::proc::MatchMode::*;
 use crate::proc::Processor;
+use minify_html_common::spec::comment::is_conditional_bang;
 
 #[inline(always)]
-pub fn process_bang(proc: &mut Processor) -> ProcessingResult<()> {
+pub fn process_bang(proc: &mut Processor, cfg: &Cfg) -> ProcessingResult<()> {
+    let checkpoint = WriteCheckpoint::new(proc);
     proc.m(IsSeq(b"<!"), Keep).expect();
     proc.m(ThroughChar(b'>'), Keep).require("bang close")?;
+    if cfg.remove_bangs {
+        // Conditional comments in the `<![if !IE]>` form are always kept, as removing them changes what's rendered.
+        let bang = checkpoint.written_range(proc);
+        if !is_conditional_bang(&proc[bang][2..bang.len() - 1]) {
+            checkpoint.erase_written(proc);
+        };
+    };
     Ok(())
 }
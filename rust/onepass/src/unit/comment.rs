@@ -1,9 +1,13 @@
-use crate::err::ProcessingResult;
+use crate::cfg::Cfg;
+use crate::err::{ErrorType, ProcessingResult};
 use crate::proc::MatchAction::*;
 use crate::proc::MatchMode::*;
 use crate::proc::Processor;
+use crate::unit::content::process_content;
 use aho_corasick::AhoCorasick;
 use lazy_static::lazy_static;
+use minify_html_common::spec::comment::{get_conditional_comment, ConditionalComment};
+use minify_html_common::spec::tag::ns::Namespace;
 
 lazy_static! {
     static ref COMMENT_END: AhoCorasick = AhoCorasick::new(&["-->"]);
@@ -16,3 +20,67 @@ pub fn process_comment(proc: &mut Processor) -> ProcessingResult<()> {
         .require("comment end")?;
     Ok(())
 }
+
+// Whether the next comment is a conditional comment, which must be processed using `process_kept_comment`.
+// Malformed conditional comments like `<!--[if IE]-->` are removed like other comments.
+pub fn is_conditional_comment(proc: &Processor) -> bool {
+    if !matches!(proc.peek_many(4, 4), Some(b"[if " | b"<![e")) {
+        return false;
+    };
+    let code_len = (4..)
+        .take_while(|&i| proc.peek(i).is_some() && proc.peek_many(i, 3) != Some(b"-->"))
+        .count();
+    proc.peek_many(4 + code_len, 3).is_some()
+        && get_conditional_comment(proc.peek_many(4, code_len).unwrap()).is_some()
+}
+
+// Minifies the HTML in a downlevel-hidden conditional comment into the start of `code`, returning the new length, or
+// None if it's malformed.
+fn process_fragment(code: &mut [u8], cfg: &Cfg, descendant_of_pre: bool) -> Option<usize> {
+    let mut proc = Processor::new_fragment(code);
+    process_content(&mut proc, cfg, Namespace::Html, None, descendant_of_pre)
+        .and_then(|_| {
+            if !proc.at_end() {
+                Err(ErrorType::UnexpectedClosingTag)
+            } else {
+                Ok(())
+            }
+        })
+        .ok()?;
+    proc.finish().ok()
+}
+
+// Processes a comment that's kept, which is written as is unless it's a downlevel-hidden conditional comment, in which
+// case its HTML is also minified.
+pub fn process_kept_comment(
+    proc: &mut Processor,
+    cfg: &Cfg,
+    descendant_of_pre: bool,
+) -> ProcessingResult<()> {
+    proc.m(IsSeq(b"<!--"), Discard).expect();
+    let code = proc
+        .m(ThroughSeq(&COMMENT_END), Discard)
+        .require("comment end")?;
+    // Copy the comment, as writing it may overwrite its source.
+    let code = proc[code][..code.len() - 3].to_vec();
+    proc.write_slice(b"<!--");
+    match get_conditional_comment(&code) {
+        Some(ConditionalComment::DownlevelHidden { opening_len }) => {
+            let closing_start = code.len() - b"<![endif]".len();
+            let mut inner = code[opening_len..closing_start].to_vec();
+            proc.write_slice(&code[..opening_len]);
+            // The minified HTML can't be used if it contains `-->`, which could come from decoded entities and would end
+            // the comment early.
+            match process_fragment(&mut inner, cfg, descendant_of_pre)
+                .filter(|&len| !inner[..len].windows(3).any(|w| w == b"-->"))
+            {
+                Some(len) => proc.write_slice(&inner[..len]),
+                None => proc.write_slice(&code[opening_len..closing_start]),
+            };
+            proc.write_slice(&code[closing_start..]);
+        }
+        _ => proc.write_slice(&code),
+    };
+    proc.write_slice(b"-->");
+    Ok(())
+}
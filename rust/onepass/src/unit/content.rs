@@ -7,7 +7,7 @@ use crate::proc::MatchAction::*;
 use crate::proc::MatchMode::*;
 use crate::proc::Processor;
 use crate::unit::bang::process_bang;
-use crate::unit::comment::process_comment;
+use crate::unit::comment::{is_conditional_comment, process_comment, process_kept_comment};
 use crate::unit::instruction::process_instruction;
 use crate::unit::tag::{process_tag, MaybeClosingTag};
 use minify_html_common::gen::codepoints::{TAG_NAME_CHAR, WHITESPACE};
@@ -64,32 +64,39 @@ pub fn process_content(
         collapse,
         destroy_whole,
         trim,
-    } = get_whitespace_minification_for_tag(proc.get_or_empty(parent), descendant_of_pre);
+    } = get_whitespace_minification_for_tag(
+        &cfg.whitespace_minification_overrides,
+        proc.get_or_empty(parent),
+        descendant_of_pre,
+    );
 
     let handle_ws = collapse || destroy_whole || trim;
 
     let mut last_written = ContentType::Start;
     // Whether or not currently in whitespace.
     let mut ws_skipped = false;
+    // If whitespace isn't collapsed, the source ranges of the whitespace currently skipped, so it can be written as is.
+    let mut ws_skipped_ranges = Vec::<ProcessorRange>::new();
     let mut prev_sibling_closing_tag = MaybeClosingTag::none();
 
     loop {
         // WARNING: Do not write anything until any previously ignored whitespace has been processed later.
 
         // Process comments, bangs, and instructions, which are completely ignored and do not affect anything (previous
-        // element node's closing tag, unintentional entities, whitespace, etc.).
+        // element node's closing tag, unintentional entities, whitespace, etc.). Conditional comments, and all comments
+        // if `keep_comments` is enabled, are kept, so they're processed later like tags.
         let next_content_type = ContentType::peek(proc);
         match next_content_type {
-            ContentType::Comment => {
+            ContentType::Comment if !cfg.keep_comments && !is_conditional_comment(proc) => {
                 process_comment(proc)?;
                 continue;
             }
             ContentType::Bang => {
-                process_bang(proc)?;
+                process_bang(proc, cfg)?;
                 continue;
             }
             ContentType::Instruction => {
-                process_instruction(proc)?;
+                process_instruction(proc, cfg)?;
                 continue;
             }
             _ => {}
@@ -98,20 +105,24 @@ pub fn process_content(
         maybe_normalise_entity(proc, false);
 
         if handle_ws {
-            if next_content_type == ContentType::Text
-                && proc.m(IsInLookup(WHITESPACE), Discard).nonempty()
-            {
-                // This is the start or part of one or more whitespace characters.
-                // Simply ignore and process until first non-whitespace.
-                ws_skipped = true;
-                continue;
+            if next_content_type == ContentType::Text {
+                let ws = proc.m(IsInLookup(WHITESPACE), Discard);
+                if ws.nonempty() {
+                    // This is the start or part of one or more whitespace characters.
+                    // Simply ignore and process until first non-whitespace.
+                    if !collapse {
+                        ws_skipped_ranges.push(ws);
+                    };
+                    ws_skipped = true;
+                    continue;
+                };
             };
 
             // Next character is not whitespace, so handle any previously ignored whitespace.
             if ws_skipped {
                 if destroy_whole
-                    && last_written == ContentType::Tag
-                    && next_content_type == ContentType::Tag
+                    && matches!(last_written, ContentType::Tag | ContentType::Comment)
+                    && matches!(next_content_type, ContentType::Tag | ContentType::Comment)
                 {
                     // Whitespace is between two tags, instructions, or bangs.
                     // `destroy_whole` is on, so don't write it.
@@ -120,19 +131,25 @@ pub fn process_content(
                 {
                     // Whitespace is leading or trailing.
                     // `trim` is on, so don't write it.
-                } else if collapse {
-                    // If writing space, then prev_sibling_closing_tag no longer represents immediate previous sibling
-                    // node; space will be new previous sibling node (as a text node).
+                } else {
+                    // If writing whitespace, then prev_sibling_closing_tag no longer represents immediate previous
+                    // sibling node; whitespace will be new previous sibling node (as a text node).
                     prev_sibling_closing_tag.write_if_exists(proc);
-                    // Current contiguous whitespace needs to be reduced to a single space character.
-                    proc.write(b' ');
+                    if collapse {
+                        // Current contiguous whitespace needs to be reduced to a single space character.
+                        proc.write(b' ');
+                    } else {
+                        // `collapse` is off, so write the whitespace as is.
+                        for r in ws_skipped_ranges.iter() {
+                            proc.write_range(*r);
+                        }
+                    };
                     last_written = ContentType::Text;
-                } else {
-                    unreachable!();
                 };
 
                 // Reset whitespace marker.
                 ws_skipped = false;
+                ws_skipped_ranges.clear();
             };
         };
 
@@ -168,9 +185,22 @@ pub fn process_content(
                 )?;
                 prev_sibling_closing_tag.replace(new_closing_tag);
             }
+            ContentType::Comment => {
+                // The comment may contain elements, so it's not safe to omit the previous sibling's closing tag.
+                prev_sibling_closing_tag.write_if_exists(proc);
+                process_kept_comment(
+                    proc,
+                    cfg,
+                    descendant_of_pre
+                        || ns == Namespace::Html
+                            && parent.filter(|p| &proc[*p] == b"pre").is_some(),
+                )?;
+            }
             ContentType::End => {
+                // The closing tags of elements at the end of a fragment are needed as the document continues after it.
                 if prev_sibling_closing_tag.exists_and(|prev_tag| {
                     !can_omit_as_last_node(proc.get_or_empty(parent), &proc[prev_tag])
+                        || parent.is_none() && proc.is_fragment()
                 }) {
                     prev_sibling_closing_tag.write(proc);
                 };
@@ -206,7 +236,7 @@ pub fn process_content(
             _ => unreachable!(),
         };
 
-        // This should not be reached if ContentType::{Comment, End}.
+        // This should not be reached if ContentType::End or the content was ignored.
         last_written = next_content_type;
     }
 
@@ -1,3 +1,4 @@
+use crate::cfg::Cfg;
 use crate::err::ProcessingResult;
 use crate::proc::MatchAction::*;
 use crate::proc::MatchMode::*;
@@ -10,9 +11,14 @@ lazy_static! {
 }
 
 #[inline(always)]
-pub fn process_instruction(proc: &mut Processor) -> ProcessingResult<()> {
-    proc.m(IsSeq(b"<?"), Keep).expect();
-    proc.m(ThroughSeq(&INSTRUCTION_END), Keep)
+pub fn process_instruction(proc: &mut Processor, cfg: &Cfg) -> ProcessingResult<()> {
+    let action = if cfg.remove_processing_instructions {
+        Discard
+    } else {
+        Keep
+    };
+    proc.m(IsSeq(b"<?"), action).expect();
+    proc.m(ThroughSeq(&INSTRUCTION_END), action)
         .require("instruction end")?;
     Ok(())
 }
@@ -2,12 +2,7 @@ use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use lazy_static::lazy_static;
 
 #[cfg(feature = "js-esbuild")]
-use {
-    crate::proc::checkpoint::WriteCheckpoint,
-    crate::proc::EsbuildSection,
-    esbuild_rs::{Loader, TransformOptions, TransformOptionsBuilder},
-    std::sync::Arc,
-};
+use {crate::proc::checkpoint::WriteCheckpoint, crate::proc::EsbuildSection};
 
 use crate::err::ProcessingResult;
 use crate::proc::MatchAction::*;
@@ -15,18 +10,6 @@ use crate::proc::MatchMode::*;
 use crate::proc::Processor;
 use crate::Cfg;
 
-#[cfg(feature = "js-esbuild")]
-lazy_static! {
-    static ref TRANSFORM_OPTIONS: Arc<TransformOptions> = {
-        let mut builder = TransformOptionsBuilder::new();
-        builder.loader = Loader::CSS;
-        builder.minify_identifiers = true;
-        builder.minify_syntax = true;
-        builder.minify_whitespace = true;
-        builder.build()
-    };
-}
-
 lazy_static! {
     static ref STYLE_END: AhoCorasick = AhoCorasickBuilder::new()
         .ascii_case_insensitive(true)
@@ -46,10 +29,11 @@ pub fn process_style(proc: &mut Processor, cfg: &Cfg) -> ProcessingResult<()> {
     if cfg.minify_css {
         let (wg, results) = proc.new_esbuild_section();
         let src = start.written_range(proc);
+        let esbuild_options = proc.esbuild_options(cfg);
         unsafe {
             esbuild_rs::transform_direct_unmanaged(
                 &proc[src],
-                &TRANSFORM_OPTIONS.clone(),
+                &esbuild_options.css,
                 move |result| {
                     let mut guard = results.lock().unwrap();
                     // TODO Are there other places that can have unintentional closing tags?
@@ -7,11 +7,12 @@ use crate::proc::MatchMode::*;
 use crate::proc::Processor;
 use crate::unit::attr::{process_attr, AttrType, ProcessedAttr};
 use crate::unit::content::process_content;
-use crate::unit::script::process_script;
+use crate::unit::script::{process_script, ScriptLang};
 use crate::unit::style::process_style;
 use lazy_static::lazy_static;
 use minify_html_common::gen::attrs::{AttributeMinification, ATTRS};
 use minify_html_common::gen::codepoints::{TAG_NAME_CHAR, WHITESPACE};
+use minify_html_common::spec::script::is_module_script_type;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::omission::{can_omit_as_before, can_omit_as_last_node};
 use minify_html_common::spec::tag::void::VOID_TAGS;
@@ -43,6 +44,7 @@ lazy_static! {
 #[derive(Copy, Clone)]
 enum TagType {
     ScriptJs,
+    ScriptJsModule,
     ScriptData,
     Style,
     Other,
@@ -111,6 +113,7 @@ pub fn process_tag(
     {
         prev_sibling_closing_tag.write(proc);
     };
+    let opening_tag_checkpoint = WriteCheckpoint::new(proc);
     // Write initially skipped left chevron.
     proc.write(b'<');
     // Write previously skipped name and use written code as range (otherwise source code will eventually be overwritten).
@@ -129,7 +132,7 @@ pub fn process_tag(
 
     loop {
         // At the beginning of this loop, the last parsed unit was either the tag name or an attribute (including its value, if it had one).
-        proc.m(WhileInLookup(WHITESPACE), Discard);
+        let ws_before_attr = proc.m(WhileInLookup(WHITESPACE), Discard).nonempty();
 
         if proc.m(IsChar(b'>'), Keep).nonempty() {
             // End of tag.
@@ -147,7 +150,7 @@ pub fn process_tag(
         let mut erase_attr = false;
 
         // Write space after tag name or unquoted/valueless attribute.
-        // Don't write after quoted.
+        // Don't write after quoted, unless `keep_spaces_between_attributes` is enabled and there was a space in the source.
         // Handle rare case where file ends in opening tag before an attribute and no minification has been done yet,
         // e.g. `<-` (yes, that's the entire file).
         if proc.at_end() {
@@ -155,10 +158,13 @@ pub fn process_tag(
         };
         match last_attr_type {
             Some(AttrType::Unquoted) | Some(AttrType::NoValue) | None => proc.write(b' '),
+            Some(AttrType::Quoted) if cfg.keep_spaces_between_attributes && ws_before_attr => {
+                proc.write(b' ')
+            }
             _ => {}
         };
 
-        let ProcessedAttr { name, typ, value } = process_attr(proc, ns, tag_name)?;
+        let ProcessedAttr { name, typ, value } = process_attr(proc, cfg, ns, tag_name)?;
         match (tag_type, &proc[name]) {
             // NOTE: We don't support multiple `type` attributes, so can't go from ScriptData => ScriptJs.
             (TagType::ScriptJs, b"type") => {
@@ -168,6 +174,8 @@ pub fn process_tag(
                     .is_none();
                 if script_tag_type_is_js {
                     erase_attr = true;
+                } else if value.filter(|v| is_module_script_type(&proc[*v])).is_some() {
+                    tag_type = TagType::ScriptJsModule;
                 } else {
                     // Tag does not contain JS, don't minify JS.
                     tag_type = TagType::ScriptData;
@@ -219,6 +227,17 @@ pub fn process_tag(
         return Ok(MaybeClosingTag(None));
     };
 
+    // The opening tag can't be erased now, as its tag name is used while processing the content, so it's removed once
+    // the whole document has been processed.
+    if ns == Namespace::Html
+        && !cfg.keep_html_and_head_opening_tags
+        && last_attr_type.is_none()
+        && matches!(&proc[tag_name], b"html" | b"head")
+    {
+        let opening_tag = opening_tag_checkpoint.written_range(proc);
+        proc.omit_written(opening_tag);
+    };
+
     let child_ns = if proc[tag_name].eq(b"svg") {
         Namespace::Svg
     } else {
@@ -227,8 +246,9 @@ pub fn process_tag(
 
     let mut closing_tag_omitted = false;
     match tag_type {
-        TagType::ScriptData => process_script(proc, cfg, false)?,
-        TagType::ScriptJs => process_script(proc, cfg, true)?,
+        TagType::ScriptData => process_script(proc, cfg, ScriptLang::Data)?,
+        TagType::ScriptJs => process_script(proc, cfg, ScriptLang::Js)?,
+        TagType::ScriptJsModule => process_script(proc, cfg, ScriptLang::JsModule)?,
         TagType::Style => process_style(proc, cfg)?,
         _ => {
             closing_tag_omitted =
@@ -237,7 +257,8 @@ pub fn process_tag(
         }
     };
 
-    let can_omit_closing_tag = can_omit_as_last_node(proc.get_or_empty(parent), &proc[tag_name]);
+    let can_omit_closing_tag = can_omit_as_last_node(proc.get_or_empty(parent), &proc[tag_name])
+        && !(parent.is_none() && proc.is_fragment());
     if closing_tag_omitted || proc.at_end() && can_omit_closing_tag {
         return Ok(MaybeClosingTag(None));
     };
@@ -263,6 +284,12 @@ pub fn process_tag(
     } else {
         proc.m(WhileInLookup(WHITESPACE), Discard);
         proc.m(IsChar(b'>'), Discard).require("closing tag end")?;
-        Ok(MaybeClosingTag(Some(tag_name)))
+        let mut closing_tag = MaybeClosingTag(Some(tag_name));
+        if cfg.keep_closing_tags {
+            // Write it now so that it isn't omitted because of the next sibling. Closing tags that are missing in the
+            // source are still omitted, as code is minified in place.
+            closing_tag.write(proc);
+        };
+        Ok(closing_tag)
     }
 }